@@ -6,14 +6,20 @@ use std::io::BufReader;
 use std::sync::Arc;
 
 pub fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("check_forms benchmark", |b| b.iter(check_forms));
+    c.bench_function("check_forms benchmark", |b| b.iter(|| check_forms(false)));
+    c.bench_function("check_forms get_form_into benchmark", |b| {
+        b.iter(|| check_forms(true))
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
 criterion_main!(benches);
 
-fn check_forms() {
+// fast: only the final forms, with get_form_into()
+fn check_forms(fast: bool) {
     let mut paradigm_line = String::new();
+    let mut r = String::new();
+    let mut r_d = String::new();
     if let Ok(pp_file) = File::open("testdata/pp.txt") {
         if let Ok(paradigm_file) = File::open("testdata/new.txt") {
             let pp_reader = BufReader::new(pp_file);
@@ -123,15 +129,23 @@ fn check_forms() {
                                             gender: None,
                                             case: None,
                                         };
-                                        let r = match form.get_form(false) {
-                                            Ok(res) => res.last().unwrap().form.to_string(),
-                                            Err(_a) => "NF".to_string(),
-                                        };
-
-                                        let r_d = match form.get_form(true) {
-                                            Ok(res) => res.last().unwrap().form.to_string(),
-                                            Err(_a) => "NDF".to_string(),
-                                        };
+                                        if fast {
+                                            if form.get_form_into(false, &mut r).is_err() {
+                                                r = "NF".to_string();
+                                            }
+                                            if form.get_form_into(true, &mut r_d).is_err() {
+                                                r_d = "NDF".to_string();
+                                            }
+                                        } else {
+                                            r = match form.get_form(false) {
+                                                Ok(res) => res.last().unwrap().form.to_string(),
+                                                Err(_a) => "NF".to_string(),
+                                            };
+                                            r_d = match form.get_form(true) {
+                                                Ok(res) => res.last().unwrap().form.to_string(),
+                                                Err(_a) => "NDF".to_string(),
+                                            };
+                                        }
 
                                         let form_line = format!(
                                             "{}{}: {} ; {}",
@@ -0,0 +1,19 @@
+fn main() {
+    // generate the C header from src/ffi.rs; tests/ffi.rs compares it with include/
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap();
+        cbindgen::Builder::new()
+            .with_crate(&crate_dir)
+            .with_config(config)
+            .generate()
+            .expect("unable to generate the C header")
+            .write_to_file(format!(
+                "{}/hoplite_verbs.h",
+                std::env::var("OUT_DIR").unwrap()
+            ));
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+    }
+}
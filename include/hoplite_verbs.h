@@ -0,0 +1,161 @@
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#ifndef HOPLITE_VERBS_H
+#define HOPLITE_VERBS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define REGULAR 0
+
+#define CONSONANT_STEM_PERFECT_PHI 1
+
+#define CONSONANT_STEM_PERFECT_MU_PI 2
+
+#define CONSONANT_STEM_PERFECT_KAPPA 4
+
+#define CONSONANT_STEM_PERFECT_SIGMA 8
+
+#define CONSONANT_STEM_PERFECT_SIGMA_2 16
+
+#define CONSONANT_STEM_PERFECT_LAMBDA 32
+
+#define CONSONANT_STEM_PERFECT_PI 64
+
+#define CONSONANT_STEM_PERFECT_BETA 128
+
+#define CONSONANT_STEM_PERFECT_GAMMA 256
+
+#define CONSONANT_STEM_PERFECT_CHI 512
+
+#define PREFIXED 1024
+
+#define CONTRACTED_FUTURE_ALPHA 2048
+
+#define CONSONANT_STEM_PERFECT_NU 4096
+
+#define MI_VERB 8192
+
+/**
+ * Result codes, the first ones mirroring HcFormError.
+ */
+typedef enum HcErrorCode {
+  HC_ERROR_CODE_OK = 0,
+  HC_ERROR_CODE_INTERNAL_ERROR = 1,
+  HC_ERROR_CODE_BLANK_PRINCIPAL_PART = 2,
+  HC_ERROR_CODE_INVALID_PRINCIPAL_PART = 3,
+  HC_ERROR_CODE_DEPONENT_NO_FORM_FOR_VOICE = 4,
+  HC_ERROR_CODE_ILLEGAL_FORM = 5,
+  HC_ERROR_CODE_DOES_NOT_EXIST = 6,
+  HC_ERROR_CODE_NO_FORM_FOR_UNIT = 7,
+  HC_ERROR_CODE_NOT_IMPLEMENTED = 8,
+  /**
+   * a null pointer or a parameter code out of range
+   */
+  HC_ERROR_CODE_INVALID_ARGUMENT = 9,
+} HcErrorCode;
+
+/**
+ * The steps of a form created by `hc_get_form`, the form itself being the last step.
+ */
+typedef struct HcFormResult HcFormResult;
+
+/**
+ * A verb created by `hc_verb_new`.
+ */
+typedef struct HcVerb HcVerb;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates a verb from a line of pp.txt: the six comma-separated principal parts,
+ * optionally followed by %unit%properties.  Returns NULL if the line is not valid.
+ *
+ * # Safety
+ * `pps` must be NULL or a NUL-terminated string.
+ */
+struct HcVerb *hc_verb_new(uint32_t id, const char *pps);
+
+/**
+ * # Safety
+ * `verb` must be NULL or a pointer returned by `hc_verb_new`, not already freed.
+ */
+void hc_verb_free(struct HcVerb *verb);
+
+/**
+ * Generates a form, storing its steps in `*out` on success (HC_ERROR_CODE_OK).
+ * `*out` is set to NULL on failure.
+ *
+ * # Safety
+ * `verb` must be a pointer returned by `hc_verb_new`, and `out` a valid pointer.
+ */
+enum HcErrorCode hc_get_form(const struct HcVerb *verb,
+                             int16_t person,
+                             int16_t number,
+                             int16_t tense,
+                             int16_t voice,
+                             int16_t mood,
+                             int16_t gender,
+                             int16_t case_,
+                             bool decompose,
+                             struct HcFormResult **out);
+
+/**
+ * # Safety
+ * `result` must be NULL or a pointer set by `hc_get_form`, not already freed.
+ */
+void hc_form_result_free(struct HcFormResult *result);
+
+/**
+ * # Safety
+ * `result` must be NULL or a pointer set by `hc_get_form`.
+ */
+size_t hc_form_result_step_count(const struct HcFormResult *result);
+
+/**
+ * The final form, to be released with `hc_string_free`.
+ *
+ * # Safety
+ * `result` must be NULL or a pointer set by `hc_get_form`.
+ */
+char *hc_form_result_form(const struct HcFormResult *result);
+
+/**
+ * The form of step `index`, to be released with `hc_string_free`; NULL if out of range.
+ *
+ * # Safety
+ * `result` must be NULL or a pointer set by `hc_get_form`.
+ */
+char *hc_form_result_step_form(const struct HcFormResult *result, size_t index);
+
+/**
+ * The explanation of step `index`, to be released with `hc_string_free`; NULL if out of
+ * range.
+ *
+ * # Safety
+ * `result` must be NULL or a pointer set by `hc_get_form`.
+ */
+char *hc_form_result_step_explanation(const struct HcFormResult *result, size_t index);
+
+/**
+ * # Safety
+ * `s` must be NULL or a string returned by this library, not already freed.
+ */
+void hc_string_free(char *s);
+
+/**
+ * The name of an error code, e.g. "BlankPrincipalPart".  The string is static and must
+ * not be freed.
+ */
+const char *hc_error_name(enum HcErrorCode code);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* HOPLITE_VERBS_H */
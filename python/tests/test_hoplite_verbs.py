@@ -0,0 +1,104 @@
+# Run from the repository root after `maturin develop`:
+#     python -m unittest discover python/tests
+
+import os
+import unittest
+
+import hoplite_verbs_rs as hc
+
+PP_FILE = os.path.join(os.path.dirname(__file__), "..", "..", "testdata", "pp.txt")
+
+
+class TestHopliteVerbs(unittest.TestCase):
+    @classmethod
+    def setUpClass(cls):
+        cls.verbs = hc.load_lexicon(PP_FILE)
+        cls.paideuo = cls.verbs[0]
+
+    def test_lexicon(self):
+        self.assertEqual(len(self.verbs), 127)
+        self.assertEqual(self.paideuo.id, 0)
+        self.assertEqual(self.paideuo.lemma, "παιδεύω")
+        self.assertEqual(len(self.paideuo.pps), 6)
+        with self.assertRaises(OSError):
+            hc.load_lexicon("no/such/file.txt")
+
+    def test_verb(self):
+        verb = hc.Verb(0, "παιδεύω, παιδεύσω, ἐπαίδευσα, πεπαίδευκα, πεπαίδευμαι, ἐπαιδεύθην % 2")
+        self.assertEqual(verb.hq_unit, 2)
+        self.assertEqual(verb.pps, self.paideuo.pps)
+        with self.assertRaises(ValueError):
+            hc.Verb(1, "παιδεύω, παιδεύσω")
+
+    def test_get_form_with_enums(self):
+        vf = hc.VerbForm(
+            self.paideuo,
+            hc.Tense.Aorist,
+            hc.Voice.Middle,
+            hc.Mood.Optative,
+            person=hc.Person.Third,
+            number=hc.Number.Plural,
+        )
+        steps = vf.get_form()
+        self.assertEqual(steps[-1].form, "παιδεύσαιντο")
+        self.assertEqual(steps[-1].explanation, "Accent verb")
+        self.assertEqual(steps[0].form, "παιδεύω, παιδεύσω, ἐπαίδευσα, πεπαίδευκα, πεπαίδευμαι, ἐπαιδεύθην")
+        self.assertEqual(vf.tense, hc.Tense.Aorist)
+        self.assertEqual(vf.person, hc.Person.Third)
+
+    def test_get_form_with_strings(self):
+        vf = hc.VerbForm(self.paideuo, "Aorist", "active", "Indicative", person="1", number="s")
+        self.assertEqual(vf.get_form()[-1].form, "ἐπαίδευσα")
+        self.assertEqual(vf.get_form(decompose=True)[-1].form, "ε ‐ παιδευσ ‐ α")
+
+        infinitive = hc.VerbForm(self.paideuo, "Aorist", "Passive", "Infinitive")
+        self.assertIsNone(infinitive.person)
+        self.assertEqual(infinitive.get_form()[-1].form, "παιδευθῆναι")
+
+        with self.assertRaises(ValueError):
+            hc.VerbForm(self.paideuo, "Aorist", "Active", "Gerund")
+
+    def test_dialect(self):
+        poieo = next(v for v in self.verbs if v.pps[0] == "ποιέω")
+        vf = hc.VerbForm(poieo, "Present", "Active", "Indicative", person="3", number="s")
+        self.assertEqual(vf.get_form()[-1].form, "ποιεῖ")
+        self.assertEqual(vf.get_form(dialect=hc.Dialect.Ionic)[-1].form, "ποιέει")
+        self.assertEqual(vf.get_form(dialect="Ionic")[-1].form, "ποιέει")
+
+    def test_errors(self):
+        pheugo = next(v for v in self.verbs if v.pps[0] == "φεύγω")
+        vf = hc.VerbForm(pheugo, "Perfect", "Passive", "Indicative", person="1", number="s")
+        with self.assertRaises(hc.BlankPrincipalPart) as cm:
+            vf.get_form()
+        self.assertIsInstance(cm.exception, hc.FormError)
+        self.assertEqual(str(cm.exception), "BlankPrincipalPart")
+
+        vf = hc.VerbForm(self.paideuo, "Present", "Active", "Imperative", person="1", number="s")
+        self.assertFalse(vf.is_legal_form())
+        with self.assertRaises(hc.FormError):
+            vf.get_form()
+
+    def test_description(self):
+        vf = hc.VerbForm(self.paideuo, "Aorist", "Middle", "Optative", person="3", number="p")
+        prev = hc.VerbForm(self.paideuo, "Aorist", "Active", "Optative", person="3", number="p")
+        self.assertEqual(vf.get_description(), "Third Plural Aorist Optative Middle")
+        self.assertEqual(
+            vf.get_description(prev, "<b>", "</b>"),
+            "Third Plural Aorist Optative <b>Middle</b>",
+        )
+
+    def test_check_pps(self):
+        answer = "παιδεύω, παιδεύσω, ἐπαίδευσα, πεπαίδευκα, πεπαίδευμαι, ἐπαίδευσα"
+        expected = [True, True, True, True, True, False]
+        self.assertEqual(hc.check_pps(answer, self.paideuo), expected)
+        self.assertEqual(self.paideuo.check_pps(answer), expected)
+        self.assertEqual(self.paideuo.check_pps("παιδεύω"), [False] * 6)
+        graves = "παιδεύω, παιδεύσω, ἐπαίδευσα, πεπαίδευκὰ, πεπαίδευμαι, ἐπαιδεύθην"
+        self.assertEqual(self.paideuo.check_pps(graves)[3], False)
+        self.assertEqual(self.paideuo.check_pps(graves, profile="lenient"), [True] * 6)
+        with self.assertRaises(ValueError):
+            self.paideuo.check_pps(answer, profile="generous")
+
+
+if __name__ == "__main__":
+    unittest.main()
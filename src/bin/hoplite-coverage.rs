@@ -0,0 +1,110 @@
+// hoplite-coverage: checks the lexicon's forms against the verbs of AGDT or PROIEL
+// treebank files, listing the mismatches and refused cells, then the totals
+//
+// usage: hoplite-coverage [--lexicon testdata/pp.txt] [--summary] <treebank.xml>...
+// exit codes: 0 every checked token matches, 1 some don't, 2 usage or i/o error
+
+use hoplite_verbs_rs::treebank::*;
+use hoplite_verbs_rs::*;
+use std::fs::File;
+use std::io::BufReader;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+static USAGE: &str = "usage: hoplite-coverage [--lexicon <file>] [--summary] <treebank.xml>...";
+
+fn main() -> ExitCode {
+    let mut lexicon = String::from("testdata/pp.txt");
+    let mut summary = false;
+    let mut files = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lexicon" => match args.next() {
+                Some(value) => lexicon = value,
+                None => return usage(),
+            },
+            "--summary" => summary = true,
+            a if a.starts_with("--") => return usage(),
+            _ => files.push(arg),
+        }
+    }
+    if files.is_empty() {
+        return usage();
+    }
+
+    let verbs = match load_lexicon(&lexicon) {
+        Ok(verbs) => verbs,
+        Err(e) => {
+            eprintln!("hoplite-coverage: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    let mut tokens = Vec::new();
+    for file in &files {
+        match File::open(file)
+            .map_err(|e| e.to_string())
+            .and_then(|f| read_verb_tokens(BufReader::new(f)))
+        {
+            Ok(t) => tokens.extend(t),
+            Err(e) => {
+                eprintln!("hoplite-coverage: can't read {}: {}", file, e);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let report = coverage(&verbs, &tokens);
+    if !summary {
+        for entry in &report.entries {
+            let t = &entry.token;
+            let found = match &entry.result {
+                HcCoverageResult::Match => continue,
+                HcCoverageResult::Mismatch {
+                    generated,
+                    mismatch,
+                } => format!("{} ({:?})", generated, mismatch),
+                HcCoverageResult::Refused(e) => format!("refused: {} ({})", e.code(), e),
+                HcCoverageResult::Unsupported(e) => format!("unsupported: {}", e),
+            };
+            println!(
+                "{}:{}\t{}\t{}\t{}\t{}",
+                t.sentence_id, t.token_id, t.lemma, t.tag, t.form, found
+            );
+        }
+    }
+
+    println!(
+        "verb tokens: {}, in the lexicon: {}",
+        tokens.len(),
+        report.entries.len()
+    );
+    println!("matching: {}", report.matches());
+    println!(
+        "mismatched: {} (accent {}, augment {}, ending {}, stem {})",
+        report.mismatches(),
+        report.mismatches_of(HcMismatch::Accent),
+        report.mismatches_of(HcMismatch::Augment),
+        report.mismatches_of(HcMismatch::Ending),
+        report.mismatches_of(HcMismatch::Stem)
+    );
+    println!("refused: {}", report.refused());
+    println!("unsupported tags: {}", report.unsupported());
+    println!("accuracy: {:.2}%", report.accuracy() * 100.0);
+
+    if report.mismatches() + report.refused() > 0 {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn usage() -> ExitCode {
+    eprintln!("{}", USAGE);
+    ExitCode::from(2)
+}
+
+fn load_lexicon(path: &str) -> Result<Vec<Arc<HcGreekVerb>>, String> {
+    let file = File::open(path).map_err(|e| format!("can't open {}: {}", path, e))?;
+    read_lexicon(BufReader::new(file)).map_err(|e| format!("{}: {}", path, e))
+}
@@ -0,0 +1,64 @@
+// hoplite-server: serves the endpoints in src/server.rs on a local port
+//
+// usage: hoplite-server [--lexicon testdata/pp.txt] [--addr 127.0.0.1:8080]
+
+use hoplite_verbs_rs::server::HcServer;
+use std::fs::File;
+use std::io::BufReader;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut lexicon = String::from("testdata/pp.txt");
+    let mut addr = String::from("127.0.0.1:8080");
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--lexicon", Some(value)) => lexicon = value,
+            ("--addr", Some(value)) => addr = value,
+            _ => {
+                eprintln!("usage: hoplite-server [--lexicon <file>] [--addr <host:port>]");
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    // the lexicon is loaded once at startup
+    let server = match File::open(&lexicon)
+        .map_err(|e| e.to_string())
+        .and_then(|f| HcServer::from_lexicon(BufReader::new(f)))
+    {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("hoplite-server: can't load {}: {}", lexicon, e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let http = match tiny_http::Server::http(&addr) {
+        Ok(http) => http,
+        Err(e) => {
+            eprintln!("hoplite-server: can't listen on {}: {}", addr, e);
+            return ExitCode::from(2);
+        }
+    };
+    eprintln!("hoplite-server: listening on http://{}", addr);
+
+    let content_type =
+        tiny_http::Header::from_bytes("Content-Type", "application/json; charset=utf-8").unwrap();
+    for mut request in http.incoming_requests() {
+        let mut body = String::new();
+        let response = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => server.handle(request.method().as_str(), request.url(), &body),
+            Err(_) => hoplite_verbs_rs::server::HcResponse {
+                status: 400,
+                body: String::from("{\"error\":\"request body is not UTF-8\"}"),
+            },
+        };
+        let _ = request.respond(
+            tiny_http::Response::from_string(response.body)
+                .with_status_code(response.status)
+                .with_header(content_type.clone()),
+        );
+    }
+    ExitCode::SUCCESS
+}
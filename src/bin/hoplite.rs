@@ -0,0 +1,710 @@
+// hoplite: generate and look up forms from a pp.txt style lexicon
+//
+// exit codes: 0 ok, 1 form not found or regressions found by diff, 2 usage or i/o error
+
+use hoplite_verbs_rs::*;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+static USAGE: &str = "usage: hoplite [options] <command> [args]
+
+commands:
+  form <lemma> <spec...>   print one form, e.g. form παιδεύω aor mid opt 3 pl
+  paradigm <lemma>         print the finite forms and infinitives of a verb
+  parse <form>             find every verb and parameter set which produce a form
+  diff [reference]         compare the lexicon's paradigms with a reference file
+                           in the format of testdata/new.txt (the default)
+  reference                print the lexicon's paradigms in the format of
+                           testdata/new.txt
+  tag [file]               lemmatize and parse the verbs of a text (default stdin),
+                           printing CoNLL-U
+  unimorph                 print every form of the lexicon as UniMorph
+  unimorph-diff <file>     compare a UniMorph file with the generated forms
+
+options:
+  --lexicon <file>         principal parts file (default testdata/pp.txt)
+  --format <fmt>           paradigm output: table (default), markdown or tsv
+  --dialect <dialect>      attic (default), ionic, homeric or koine
+  --extended-perfect       include forms beyond the H&Q syllabus
+  --decompose              print decomposed forms
+  --steps                  print each step of the derivation (form only)
+
+a spec is a list of tense, voice, mood, person, number, gender and case in any order:
+  pres impf fut aor perf plup, act mid pass, ind subj opt imper inf ptc,
+  1 2 3, sg pl, masc fem neut, nom gen dat acc voc";
+
+const EXIT_NOT_FOUND: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+
+static TENSES: [HcTense; 6] = [
+    HcTense::Present,
+    HcTense::Imperfect,
+    HcTense::Future,
+    HcTense::Aorist,
+    HcTense::Perfect,
+    HcTense::Pluperfect,
+];
+static VOICES: [HcVoice; 3] = [HcVoice::Active, HcVoice::Middle, HcVoice::Passive];
+static FINITE_MOODS: [HcMood; 4] = [
+    HcMood::Indicative,
+    HcMood::Subjunctive,
+    HcMood::Optative,
+    HcMood::Imperative,
+];
+static PERSONS: [HcPerson; 3] = [HcPerson::First, HcPerson::Second, HcPerson::Third];
+static NUMBERS: [HcNumber; 2] = [HcNumber::Singular, HcNumber::Plural];
+
+#[derive(PartialEq, Debug)]
+enum Format {
+    Table,
+    Markdown,
+    Tsv,
+}
+
+struct Options {
+    lexicon: String,
+    format: Format,
+    form_options: HcFormOptions,
+    decompose: bool,
+    steps: bool,
+}
+
+#[derive(Default, PartialEq, Debug)]
+struct Spec {
+    person: Option<HcPerson>,
+    number: Option<HcNumber>,
+    tense: Option<HcTense>,
+    voice: Option<HcVoice>,
+    mood: Option<HcMood>,
+    gender: Option<HcGender>,
+    case: Option<HcCase>,
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(code) => code,
+        Err(msg) => {
+            eprintln!("hoplite: {}", msg);
+            ExitCode::from(EXIT_USAGE)
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut options = Options {
+        lexicon: String::from("testdata/pp.txt"),
+        format: Format::Table,
+        form_options: HcFormOptions::default(),
+        decompose: false,
+        steps: false,
+    };
+
+    let mut rest = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--lexicon" => options.lexicon = next_value(&mut iter, arg)?.to_string(),
+            "--format" => {
+                options.format = match next_value(&mut iter, arg)? {
+                    "table" => Format::Table,
+                    "markdown" | "md" => Format::Markdown,
+                    "tsv" => Format::Tsv,
+                    f => return Err(format!("unknown format: {}", f)),
+                }
+            }
+            "--dialect" => {
+                options.form_options.dialect = match next_value(&mut iter, arg)? {
+                    "attic" => HcDialect::Attic,
+                    "ionic" => HcDialect::Ionic,
+                    "homeric" => HcDialect::Homeric,
+                    "koine" => HcDialect::Koine,
+                    d => return Err(format!("unknown dialect: {}", d)),
+                }
+            }
+            "--extended-perfect" => options.form_options.extended_perfect = true,
+            "--decompose" => options.decompose = true,
+            "--steps" => options.steps = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(ExitCode::SUCCESS);
+            }
+            a if a.starts_with("--") => return Err(format!("unknown option: {}\n\n{}", a, USAGE)),
+            // forms are generated with tonos where some keyboards produce oxia
+            a => rest.push(input::oxia(a)),
+        }
+    }
+
+    let Some((command, command_args)) = rest.split_first() else {
+        return Err(USAGE.to_string());
+    };
+    match command.as_str() {
+        "form" => {
+            let verbs = load_lexicon(&options.lexicon)?;
+            let (lemma, spec) = command_args
+                .split_first()
+                .ok_or("form needs a lemma and a spec")?;
+            let verb = find_verb(&verbs, lemma)?;
+            print_form(&options, verb, &parse_spec(spec)?)
+        }
+        "paradigm" => {
+            let verbs = load_lexicon(&options.lexicon)?;
+            let lemma = command_args.first().ok_or("paradigm needs a lemma")?;
+            print_paradigm(&options, find_verb(&verbs, lemma)?);
+            Ok(ExitCode::SUCCESS)
+        }
+        "parse" => {
+            let verbs = load_lexicon(&options.lexicon)?;
+            let form = command_args.first().ok_or("parse needs a form")?;
+            Ok(print_parses(&options, &verbs, form))
+        }
+        "diff" => {
+            let verbs = load_lexicon(&options.lexicon)?;
+            let reference = command_args
+                .first()
+                .map(|s| s.as_str())
+                .unwrap_or("testdata/new.txt");
+            diff(&options, &verbs, reference)
+        }
+        "reference" => {
+            let verbs = load_lexicon(&options.lexicon)?;
+            for line in reference_lines(&options, &verbs) {
+                println!("{}", line);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        "tag" => {
+            let verbs = load_lexicon(&options.lexicon)?;
+            let mut text = String::new();
+            match command_args.first() {
+                Some(path) => File::open(path)
+                    .and_then(|mut f| f.read_to_string(&mut text))
+                    .map_err(|e| format!("can't read {}: {}", path, e))?,
+                None => std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|e| format!("can't read stdin: {}", e))?,
+            };
+            let tagger = tagger::HcTagger::new(&verbs).map_err(|e| e.to_string())?;
+            print!("{}", tagger::to_conllu(&tagger.tag(&text)));
+            Ok(ExitCode::SUCCESS)
+        }
+        "unimorph" => {
+            let verbs = load_lexicon(&options.lexicon)?;
+            let stdout = std::io::stdout();
+            unimorph::export(&verbs, &mut stdout.lock())
+                .map_err(|e| format!("can't write: {}", e))?;
+            Ok(ExitCode::SUCCESS)
+        }
+        "unimorph-diff" => {
+            let verbs = load_lexicon(&options.lexicon)?;
+            let path = command_args.first().ok_or("unimorph-diff needs a file")?;
+            let rows = File::open(path)
+                .and_then(|f| unimorph::import(BufReader::new(f)))
+                .map_err(|e| format!("can't read {}: {}", path, e))?;
+            Ok(unimorph_diff(&verbs, &rows, path))
+        }
+        "help" => {
+            println!("{}", USAGE);
+            Ok(ExitCode::SUCCESS)
+        }
+        c => Err(format!("unknown command: {}\n\n{}", c, USAGE)),
+    }
+}
+
+fn next_value<'a>(iter: &mut std::slice::Iter<'a, String>, arg: &str) -> Result<&'a str, String> {
+    iter.next()
+        .map(|s| s.as_str())
+        .ok_or(format!("{} needs a value", arg))
+}
+
+fn load_lexicon(path: &str) -> Result<Vec<Arc<HcGreekVerb>>, String> {
+    let file = File::open(path).map_err(|e| format!("can't open {}: {}", path, e))?;
+    read_lexicon(BufReader::new(file)).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn strip_accents(s: &str) -> String {
+    hgk_strip_diacritics(s, HGK_ACUTE | HGK_CIRCUMFLEX | HGK_GRAVE | HGK_MACRON)
+}
+
+// by first principal part, with or without accents, or by line number in the lexicon
+fn find_verb<'a>(
+    verbs: &'a [Arc<HcGreekVerb>],
+    lemma: &str,
+) -> Result<&'a Arc<HcGreekVerb>, String> {
+    if let Ok(idx) = lemma.parse::<usize>() {
+        return verbs.get(idx).ok_or(format!("no verb number {}", idx));
+    }
+    verbs
+        .iter()
+        .find(|v| v.pps[0] == lemma)
+        .or_else(|| {
+            let unaccented = strip_accents(lemma);
+            verbs
+                .iter()
+                .find(|v| strip_accents(&v.pps[0]) == unaccented)
+        })
+        .ok_or(format!("no verb {} in the lexicon", lemma))
+}
+
+fn parse_spec(words: &[String]) -> Result<Spec, String> {
+    let mut spec = Spec::default();
+    for word in words {
+        match word.to_lowercase().as_str() {
+            "1" | "1st" => spec.person = Some(HcPerson::First),
+            "2" | "2nd" => spec.person = Some(HcPerson::Second),
+            "3" | "3rd" => spec.person = Some(HcPerson::Third),
+            "s" | "sg" | "sing" | "singular" => spec.number = Some(HcNumber::Singular),
+            "p" | "pl" | "plur" | "plural" => spec.number = Some(HcNumber::Plural),
+            "pres" | "present" => spec.tense = Some(HcTense::Present),
+            "impf" | "imperf" | "imperfect" => spec.tense = Some(HcTense::Imperfect),
+            "fut" | "future" => spec.tense = Some(HcTense::Future),
+            "aor" | "aorist" => spec.tense = Some(HcTense::Aorist),
+            "perf" | "pf" | "perfect" => spec.tense = Some(HcTense::Perfect),
+            "plup" | "plpf" | "pluperfect" => spec.tense = Some(HcTense::Pluperfect),
+            "act" | "active" => spec.voice = Some(HcVoice::Active),
+            "mid" | "middle" => spec.voice = Some(HcVoice::Middle),
+            "pass" | "passive" => spec.voice = Some(HcVoice::Passive),
+            "ind" | "indic" | "indicative" => spec.mood = Some(HcMood::Indicative),
+            "subj" | "subjunctive" => spec.mood = Some(HcMood::Subjunctive),
+            "opt" | "optative" => spec.mood = Some(HcMood::Optative),
+            "imper" | "imv" | "imperative" => spec.mood = Some(HcMood::Imperative),
+            "inf" | "infinitive" => spec.mood = Some(HcMood::Infinitive),
+            "ptc" | "part" | "participle" => spec.mood = Some(HcMood::Participle),
+            "masc" | "m" | "masculine" => spec.gender = Some(HcGender::Masculine),
+            "fem" | "f" | "feminine" => spec.gender = Some(HcGender::Feminine),
+            "neut" | "n" | "neuter" => spec.gender = Some(HcGender::Neuter),
+            "nom" | "nominative" => spec.case = Some(HcCase::Nominative),
+            "gen" | "genitive" => spec.case = Some(HcCase::Genitive),
+            "dat" | "dative" => spec.case = Some(HcCase::Dative),
+            "acc" | "accusative" => spec.case = Some(HcCase::Accusative),
+            "voc" | "vocative" => spec.case = Some(HcCase::Vocative),
+            w => return Err(format!("unknown word in spec: {}", w)),
+        }
+    }
+    if spec.tense.is_none() || spec.voice.is_none() || spec.mood.is_none() {
+        return Err(String::from(
+            "a spec needs at least a tense, a voice and a mood",
+        ));
+    }
+    Ok(spec)
+}
+
+fn spec_string(vf: &HcGreekVerbForm) -> String {
+    let tense = match vf.tense {
+        HcTense::Present => "pres",
+        HcTense::Imperfect => "impf",
+        HcTense::Future => "fut",
+        HcTense::Aorist => "aor",
+        HcTense::Perfect => "perf",
+        HcTense::Pluperfect => "plup",
+    };
+    let voice = match vf.voice {
+        HcVoice::Active => "act",
+        HcVoice::Middle => "mid",
+        HcVoice::Passive => "pass",
+    };
+    let mood = match vf.mood {
+        HcMood::Indicative => "ind",
+        HcMood::Subjunctive => "subj",
+        HcMood::Optative => "opt",
+        HcMood::Imperative => "imper",
+        HcMood::Infinitive => "inf",
+        HcMood::Participle => "ptc",
+    };
+    let mut s = format!("{} {} {}", tense, voice, mood);
+    if let (Some(p), Some(n)) = (vf.person, vf.number) {
+        let n = if n == HcNumber::Singular { "sg" } else { "pl" };
+        s = format!("{} {} {}", s, p.value(), n);
+    }
+    s
+}
+
+fn get_form(options: &Options, vf: &HcGreekVerbForm) -> Result<Vec<Step>, HcFormError> {
+    vf.get_form_with_options(options.decompose, &options.form_options)
+}
+
+fn print_form(options: &Options, verb: &Arc<HcGreekVerb>, spec: &Spec) -> Result<ExitCode, String> {
+    let vf = HcGreekVerbForm {
+        verb: verb.clone(),
+        person: spec.person,
+        number: spec.number,
+        tense: spec.tense.unwrap(),
+        voice: spec.voice.unwrap(),
+        mood: spec.mood.unwrap(),
+        gender: spec.gender,
+        case: spec.case,
+    };
+    match get_form(options, &vf) {
+        Ok(steps) => {
+            if options.steps {
+                for step in &steps {
+                    println!("{}: {}", step.explanation, step.form);
+                }
+            } else {
+                println!("{}", steps.last().unwrap().form);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(e) => {
+            eprintln!("{} {}: {}", verb.pps[0], spec_string(&vf), e);
+            Ok(ExitCode::from(EXIT_NOT_FOUND))
+        }
+    }
+}
+
+fn form_or_blank(options: &Options, vf: &HcGreekVerbForm) -> String {
+    match get_form(options, vf) {
+        Ok(steps) => steps.last().unwrap().form.to_string(),
+        Err(_) => String::from("—"),
+    }
+}
+
+// the same combinations of tense, voice and mood as testdata/new.txt
+fn finite_sections(verb: &HcGreekVerb) -> Vec<(HcTense, HcVoice, HcMood)> {
+    let mut sections = Vec::new();
+    for tense in TENSES {
+        for voice in VOICES {
+            for mood in FINITE_MOODS {
+                let oida = verb.pps[0] == "οἶδα" || verb.pps[0] == "σύνοιδα";
+                if mood != HcMood::Indicative
+                    && (tense == HcTense::Imperfect
+                        || tense == HcTense::Pluperfect
+                        || (tense == HcTense::Perfect && !(oida && voice == HcVoice::Active))
+                        || (tense == HcTense::Future && mood != HcMood::Optative))
+                {
+                    continue;
+                }
+                sections.push((tense, voice, mood));
+            }
+        }
+    }
+    sections
+}
+
+fn section_label(verb: &HcGreekVerb, tense: HcTense, voice: HcVoice, mood: HcMood) -> String {
+    format!(
+        "{} {} {}",
+        tense.value(),
+        get_voice_label(tense, voice, mood, verb.deponent_type()),
+        mood.value()
+    )
+}
+
+fn finite_form(
+    verb: &Arc<HcGreekVerb>,
+    tense: HcTense,
+    voice: HcVoice,
+    mood: HcMood,
+    person: HcPerson,
+    number: HcNumber,
+) -> HcGreekVerbForm {
+    HcGreekVerbForm {
+        verb: verb.clone(),
+        person: Some(person),
+        number: Some(number),
+        tense,
+        voice,
+        mood,
+        gender: None,
+        case: None,
+    }
+}
+
+fn infinitive_form(verb: &Arc<HcGreekVerb>, tense: HcTense, voice: HcVoice) -> HcGreekVerbForm {
+    HcGreekVerbForm {
+        verb: verb.clone(),
+        person: None,
+        number: None,
+        tense,
+        voice,
+        mood: HcMood::Infinitive,
+        gender: None,
+        case: None,
+    }
+}
+
+fn infinitive_tenses() -> [HcTense; 4] {
+    [
+        HcTense::Present,
+        HcTense::Future,
+        HcTense::Aorist,
+        HcTense::Perfect,
+    ]
+}
+
+// pad by letters, not chars, so combining diacritics don't throw the columns off
+fn pad(s: &str, width: usize) -> String {
+    let len = s
+        .chars()
+        .filter(|c| !('\u{0300}'..='\u{036F}').contains(c))
+        .count();
+    format!("{}{}", s, " ".repeat(width.saturating_sub(len)))
+}
+
+fn print_paradigm(options: &Options, verb: &Arc<HcGreekVerb>) {
+    match options.format {
+        Format::Table => println!("{}\n", verb.pps.join(", ")),
+        Format::Markdown => println!("## {}\n", verb.pps.join(", ")),
+        Format::Tsv => println!("lemma\ttense\tvoice\tmood\tperson\tnumber\tform"),
+    }
+
+    for (tense, voice, mood) in finite_sections(verb) {
+        let label = section_label(verb, tense, voice, mood);
+        let rows: Vec<[String; 2]> = PERSONS
+            .iter()
+            .map(|p| {
+                NUMBERS
+                    .map(|n| form_or_blank(options, &finite_form(verb, tense, voice, mood, *p, n)))
+            })
+            .collect();
+
+        match options.format {
+            Format::Table => {
+                println!("{}", label);
+                for (p, row) in PERSONS.iter().zip(&rows) {
+                    println!("  {}  {}  {}", p.value(), pad(&row[0], 30), row[1]);
+                }
+                println!();
+            }
+            Format::Markdown => {
+                println!("### {}\n\n| | Singular | Plural |\n|---|---|---|", label);
+                for (p, row) in PERSONS.iter().zip(&rows) {
+                    println!("| {} | {} | {} |", p.value(), row[0], row[1]);
+                }
+                println!();
+            }
+            Format::Tsv => {
+                for (n, number) in NUMBERS.iter().enumerate() {
+                    for (p, row) in PERSONS.iter().zip(&rows) {
+                        println!(
+                            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                            verb.pps[0],
+                            tense.value(),
+                            voice.value(),
+                            mood.value(),
+                            p.value(),
+                            number.value(),
+                            row[n]
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    match options.format {
+        Format::Table => println!("Infinitives"),
+        Format::Markdown => {
+            println!("### Infinitives\n\n| | Active | Middle | Passive |\n|---|---|---|---|")
+        }
+        Format::Tsv => (),
+    }
+    for tense in infinitive_tenses() {
+        let row = VOICES.map(|v| form_or_blank(options, &infinitive_form(verb, tense, v)));
+        match options.format {
+            Format::Table => println!(
+                "  {}  {}  {}  {}",
+                pad(tense.value(), 10),
+                pad(&row[0], 20),
+                pad(&row[1], 20),
+                row[2]
+            ),
+            Format::Markdown => println!(
+                "| {} | {} | {} | {} |",
+                tense.value(),
+                row[0],
+                row[1],
+                row[2]
+            ),
+            Format::Tsv => {
+                for (voice, form) in VOICES.iter().zip(&row) {
+                    println!(
+                        "{}\t{}\t{}\tInfinitive\t\t\t{}",
+                        verb.pps[0],
+                        tense.value(),
+                        voice.value(),
+                        form
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn print_parses(options: &Options, verbs: &[Arc<HcGreekVerb>], form: &str) -> ExitCode {
+    let unaccented = strip_accents(form);
+    let mut exact = Vec::new();
+    let mut without_accents = Vec::new();
+
+    for verb in verbs {
+        let mut candidates = Vec::new();
+        for (tense, voice, mood) in finite_sections(verb) {
+            for number in NUMBERS {
+                for person in PERSONS {
+                    candidates.push(finite_form(verb, tense, voice, mood, person, number));
+                }
+            }
+        }
+        for tense in infinitive_tenses() {
+            for voice in VOICES {
+                candidates.push(infinitive_form(verb, tense, voice));
+            }
+        }
+
+        for vf in candidates {
+            let Ok(steps) = get_form(options, &vf) else {
+                continue;
+            };
+            let result = format!("{} {}", verb.pps[0], spec_string(&vf));
+            for alt in steps.last().unwrap().form.split(" / ") {
+                if alt == form {
+                    exact.push(result.clone());
+                } else if strip_accents(alt) == unaccented {
+                    without_accents.push(format!("{} ({})", result, alt));
+                }
+            }
+        }
+    }
+
+    // only fall back to ignoring accents if nothing matches exactly
+    let found = if exact.is_empty() {
+        without_accents
+    } else {
+        exact
+    };
+    for line in &found {
+        println!("{}", line);
+    }
+    if found.is_empty() {
+        eprintln!("no parse for {}", form);
+        ExitCode::from(EXIT_NOT_FOUND)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+// the paradigms in the format of testdata/new.txt, see check_forms() in lib.rs
+fn reference_lines(options: &Options, verbs: &[Arc<HcGreekVerb>]) -> Vec<String> {
+    // every form is generated first (in parallel with the rayon feature), then laid out
+    let mut forms = Vec::new();
+    for verb in verbs {
+        for (tense, voice, mood) in finite_sections(verb) {
+            for number in NUMBERS {
+                for person in PERSONS {
+                    forms.push(finite_form(verb, tense, voice, mood, person, number));
+                }
+            }
+        }
+    }
+    let mut generated = generate_forms(&forms, false, &options.form_options).into_iter();
+    let mut decomposed = generate_forms(&forms, true, &options.form_options).into_iter();
+
+    let mut lines = Vec::new();
+    for (idx, verb) in verbs.iter().enumerate() {
+        let partial = if verb.deponent_type() == HcDeponentType::NotDeponent {
+            String::new()
+        } else {
+            format!(" ({})", verb.deponent_type().value())
+        };
+        let lemma = if verb.pps[0] != "—" {
+            &verb.pps[0]
+        } else {
+            &verb.pps[1]
+        };
+        lines.push(String::new());
+        lines.push(format!("Verb {}. {}{}", idx, lemma, partial));
+
+        for (tense, voice, mood) in finite_sections(verb) {
+            lines.push(String::new());
+            lines.push(section_label(verb, tense, voice, mood));
+            for number in NUMBERS {
+                for person in PERSONS {
+                    let form = generated.next().unwrap().unwrap_or(String::from("NF"));
+                    let decomposed = decomposed.next().unwrap().unwrap_or(String::from("NDF"));
+                    lines.push(format!(
+                        "{}{}: {} ; {}",
+                        person.value(),
+                        number.value(),
+                        form.replace(" /", ","),
+                        decomposed.replace(" /", ",")
+                    ));
+                }
+            }
+        }
+    }
+    lines
+}
+
+fn diff(
+    options: &Options,
+    verbs: &[Arc<HcGreekVerb>],
+    reference: &str,
+) -> Result<ExitCode, String> {
+    let file = File::open(reference).map_err(|e| format!("can't open {}: {}", reference, e))?;
+    let expected: Vec<String> = BufReader::new(file)
+        .lines()
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("can't read {}: {}", reference, e))?;
+    let actual = reference_lines(options, verbs);
+
+    // lines after the last paradigm (e.g. the longest form statistics) are not compared,
+    // and neither are the tense/voice/mood labels, as in check_forms()
+    let mut differences = 0;
+    let mut section = "";
+    for (n, line) in actual.iter().enumerate() {
+        if line.starts_with("Verb ") {
+            section = line;
+        } else if !line.is_empty() && !line.contains(": ") {
+            continue;
+        }
+        match expected.get(n) {
+            Some(e) if e == line => (),
+            e => {
+                differences += 1;
+                println!("{}: {}", reference, n + 1);
+                println!("  in {}", section);
+                println!("- {}", e.map(|s| s.as_str()).unwrap_or("(missing)"));
+                println!("+ {}", line);
+            }
+        }
+    }
+
+    if differences > 0 {
+        println!("lines differing: {}", differences);
+        Ok(ExitCode::from(EXIT_NOT_FOUND))
+    } else {
+        println!("no differences");
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn unimorph_diff(
+    verbs: &[Arc<HcGreekVerb>],
+    rows: &[unimorph::HcUniMorphRow],
+    path: &str,
+) -> ExitCode {
+    let comparison = unimorph::compare(verbs, rows);
+    for d in &comparison.differences {
+        println!("{}: {}", path, d.row.line);
+        println!("  {} {}", d.row.lemma, d.row.features);
+        println!("- {}", d.row.form);
+        match &d.generated {
+            Ok(form) => println!("+ {}", form),
+            Err(e) => println!("+ ({})", e),
+        }
+    }
+    println!(
+        "matching: {}, differing: {}, unknown lemmas: {}, unsupported: {}",
+        comparison.matching,
+        comparison.differences.len(),
+        comparison.unknown_lemmas.len(),
+        comparison.unsupported.len()
+    );
+    if comparison.differences.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(EXIT_NOT_FOUND)
+    }
+}
@@ -0,0 +1,175 @@
+//! A memoizing cache in front of `get_form`, shared between threads.
+//!
+//! Entries are keyed by the verb's id and the form's parameters, so a cache should only
+//! hold verbs from one lexicon.  Errors are cached as well as forms.
+
+use super::*;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
+struct FormKey {
+    verb_id: u32,
+    person: Option<HcPerson>,
+    number: Option<HcNumber>,
+    tense: HcTense,
+    voice: HcVoice,
+    mood: HcMood,
+    gender: Option<HcGender>,
+    case: Option<HcCase>,
+    decompose: bool,
+}
+
+impl FormKey {
+    fn new(vf: &HcGreekVerbForm, decompose: bool) -> FormKey {
+        FormKey {
+            verb_id: vf.verb.id,
+            person: vf.person,
+            number: vf.number,
+            tense: vf.tense,
+            voice: vf.voice,
+            mood: vf.mood,
+            gender: vf.gender,
+            case: vf.case,
+            decompose,
+        }
+    }
+}
+
+type CachedForm = Result<Arc<Vec<Step>>, HcFormError>;
+
+#[derive(Default)]
+struct CacheEntries {
+    forms: HashMap<FormKey, CachedForm>,
+    // insertion order, for evicting the oldest entry
+    order: VecDeque<FormKey>,
+}
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Default)]
+pub struct FormCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+}
+
+impl FormCacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        if self.hits + self.misses == 0 {
+            0.0
+        } else {
+            self.hits as f64 / (self.hits + self.misses) as f64
+        }
+    }
+}
+
+pub struct FormCache {
+    capacity: usize,
+    evict: bool,
+    entries: Mutex<CacheEntries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl FormCache {
+    /// a cache of at most `capacity` forms, evicting the oldest entry when full
+    pub fn new(capacity: usize) -> FormCache {
+        FormCache::with_eviction(capacity, true)
+    }
+
+    /// without eviction a full cache keeps what it has and new forms are computed
+    /// each time
+    pub fn with_eviction(capacity: usize, evict: bool) -> FormCache {
+        FormCache {
+            capacity,
+            evict,
+            entries: Mutex::new(CacheEntries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// get_form(decompose), from the cache if it has been asked for before
+    pub fn get_form(&self, vf: &HcGreekVerbForm, decompose: bool) -> CachedForm {
+        let key = FormKey::new(vf, decompose);
+        if let Some(cached) = self.entries.lock().unwrap().forms.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return cached.clone();
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        // computed without holding the lock; two threads may race to insert the same form
+        let form = vf.get_form(decompose).map(Arc::new);
+        self.insert(key, form.clone());
+        form
+    }
+
+    /// caches every finite form and infinitive of the verbs, both plain and decomposed;
+    /// returns the number of forms added
+    pub fn prewarm(&self, verbs: &[Arc<HcGreekVerb>]) -> usize {
+        let mut added = 0;
+        for verb in verbs {
+            for vf in paradigm_forms(verb) {
+                for decompose in [false, true] {
+                    let key = FormKey::new(&vf, decompose);
+                    if self.entries.lock().unwrap().forms.contains_key(&key) {
+                        continue;
+                    }
+                    if self.insert(key, vf.get_form(decompose).map(Arc::new)) {
+                        added += 1;
+                    }
+                }
+            }
+        }
+        added
+    }
+
+    fn insert(&self, key: FormKey, form: CachedForm) -> bool {
+        if self.capacity == 0 {
+            return false;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.forms.contains_key(&key) {
+            return false;
+        }
+        if entries.forms.len() >= self.capacity {
+            if !self.evict {
+                return false;
+            }
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.forms.remove(&oldest);
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        entries.forms.insert(key, form);
+        entries.order.push_back(key);
+        true
+    }
+
+    pub fn stats(&self) -> FormCacheStats {
+        FormCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries: self.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().forms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// empties the cache; the statistics are kept
+    pub fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.forms.clear();
+        entries.order.clear();
+    }
+}
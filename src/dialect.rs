@@ -0,0 +1,494 @@
+use super::*;
+
+// Dialect forms are derived from the Attic form, each change being recorded as its own
+// step.  Ionic and Homeric: Smyth 653-656 (augment), 495 (iteratives), 469 (-μεναι), 457
+// and 463 (epic subjunctive).  Koine: BDF 80-84 (endings), 65 (optative), 34 (σσ).
+// Only finite forms and infinitives are covered, not participles, except for σσ.
+
+// the Attic α and ο rules: ε-contracts stay open in Ionic (ποιέει, ποιέουσι)
+static IONIC_CONTRACTIONS: ContractionRules = ContractionRules {
+    rules: &[
+        ("αει", "ᾱͅ"),
+        ("αε", "ᾱ"),
+        ("αη", "ᾱ"),
+        ("αῃ", "ᾱͅ"),
+        ("αοι", "ῳ"),
+        ("αου", "ω"),
+        ("αο", "ω"),
+        ("αω", "ω"),
+        ("οει", "οι"),
+        ("οε", "ου"),
+        ("οη", "ω"),
+        ("οῃ", "οι"),
+        ("οοι", "οι"),
+        ("οου", "ου"),
+        ("οο", "ου"),
+        ("οω", "ω"),
+    ],
+    open_is_recessive: true,
+};
+
+static ITERATIVE_ENDINGS: &[[&str; 6]; 2] = &[
+    ["ον", "ες", "ε(ν)", "ομεν", "ετε", "ον"],
+    ["ομην", "εο", "ετο", "ομεθα", "εσθε", "οντο"],
+];
+
+// (Attic long vowel, epic short vowel) subjunctive endings of the sigmatic aorist
+static SHORT_VOWEL_SUBJUNCTIVE: &[(&str, &str); 7] = &[
+    ("ωμεν", "ομεν"),
+    ("ητε", "ετε"),
+    ("ωμαι", "ομαι"),
+    ("ηται", "εται"),
+    ("ωμεθα", "ομεθα"),
+    ("ησθε", "εσθε"),
+    ("ωνται", "ονται"),
+];
+
+// (second aorist, first aorist) active indicative endings: εἶπα, εἶπαν beside εἶπον
+static KOINE_SECOND_AORIST: &[(&str, &str); 6] = &[
+    ("ον", "α"),
+    ("ες", "ας"),
+    ("ε(ν)", "ε(ν)"),
+    ("ομεν", "αμεν"),
+    ("ετε", "ατε"),
+    ("ον", "αν"),
+];
+
+// optatives which survive in the New Testament as set phrases (μὴ γένοιτο)
+static KOINE_OPTATIVES: &[&str; 7] = &[
+    "γένοιτο",
+    "εἴη",
+    "εἴησαν",
+    "δοίη",
+    "εὐξαίμην",
+    "ἔχοι",
+    "βούλοιτο",
+];
+
+pub fn apply_dialect(
+    vf: &HcGreekVerbForm,
+    dialect: HcDialect,
+    steps: &mut Vec<Step>,
+) -> Result<(), HcFormError> {
+    if steps.len() < 3 {
+        return Ok(());
+    }
+    match dialect {
+        HcDialect::Attic => (),
+        HcDialect::Ionic | HcDialect::Homeric => apply_epic(vf, dialect, steps),
+        HcDialect::Koine => apply_koine(vf, steps)?,
+    }
+    Ok(())
+}
+
+fn last_forms(steps: &[Step]) -> Vec<String> {
+    steps
+        .last()
+        .unwrap()
+        .form
+        .split(" / ")
+        .map(|f| f.to_string())
+        .collect()
+}
+
+fn apply_epic(vf: &HcGreekVerbForm, dialect: HcDialect, steps: &mut Vec<Step>) {
+    let mut forms = last_forms(steps);
+
+    let uncontracted = uncontracted_forms(vf, steps);
+    if !uncontracted.is_empty() {
+        if dialect == HcDialect::Ionic {
+            forms = uncontracted;
+        } else {
+            // Homer has both φιλεῖ and φιλέει
+            forms.extend(uncontracted);
+        }
+        push_dialect_step(steps, forms.clone(), "Leave ε-contraction open", dialect);
+    }
+
+    if dialect == HcDialect::Homeric {
+        let unaugmented = unaugmented_forms(vf, &forms);
+        if !unaugmented.is_empty() {
+            forms.extend(unaugmented);
+            push_dialect_step(steps, forms.clone(), "Omit augment", dialect);
+        }
+    }
+
+    let iteratives = iterative_forms(vf);
+    if !iteratives.is_empty() {
+        forms.extend(iteratives);
+        push_dialect_step(steps, forms.clone(), "Add iterative in -σκ-", dialect);
+    }
+
+    if dialect == HcDialect::Homeric {
+        let infinitives = epic_infinitives(vf, &forms);
+        if !infinitives.is_empty() {
+            forms.extend(infinitives);
+            push_dialect_step(steps, forms.clone(), "Add infinitive in -μεν(αι)", dialect);
+        }
+
+        let subjunctives = epic_subjunctives(vf, &forms);
+        if !subjunctives.is_empty() {
+            forms.extend(subjunctives);
+            push_dialect_step(steps, forms, "Add epic subjunctive", dialect);
+        }
+    }
+}
+
+fn push_dialect_step(
+    steps: &mut Vec<Step>,
+    mut forms: Vec<String>,
+    explanation: &str,
+    dialect: HcDialect,
+) {
+    let mut unique = HashSet::new();
+    forms.retain(|item| unique.insert(item.clone()));
+    let form = forms.join(" / ");
+    if steps.last().is_some_and(|s| s.form == form) {
+        return;
+    }
+    steps.push(Step {
+        form,
+        explanation: format!("{} ({})", explanation, dialect.value()),
+    });
+}
+
+fn person_number_index(vf: &HcGreekVerbForm) -> Option<usize> {
+    match (vf.person, vf.number) {
+        (Some(HcPerson::First), Some(HcNumber::Singular)) => Some(0),
+        (Some(HcPerson::Second), Some(HcNumber::Singular)) => Some(1),
+        (Some(HcPerson::Third), Some(HcNumber::Singular)) => Some(2),
+        (Some(HcPerson::First), Some(HcNumber::Plural)) => Some(3),
+        (Some(HcPerson::Second), Some(HcNumber::Plural)) => Some(4),
+        (Some(HcPerson::Third), Some(HcNumber::Plural)) => Some(5),
+        _ => None,
+    }
+}
+
+fn strip_accents(form: &str) -> String {
+    hgk_strip_diacritics(form, HGK_ACUTE | HGK_CIRCUMFLEX | HGK_GRAVE)
+}
+
+// not every μι verb in pp.txt has the MI_VERB flag
+fn is_mi_verb(vf: &HcGreekVerbForm) -> bool {
+    vf.verb.pps[0].ends_with("μι")
+}
+
+// infinitives are normally accented by accent_infinitive, but the epic ones are recessive
+fn accent_recessive(vf: &HcGreekVerbForm, form: &str) -> String {
+    if vf.mood == HcMood::Infinitive {
+        let probe = HcGreekVerbForm {
+            mood: HcMood::Indicative,
+            ..vf.clone()
+        };
+        probe.accent_verb(form)
+    } else {
+        vf.accent_verb(form)
+    }
+}
+
+// contracted verbs: redo the contraction of the uncontracted forms with the Ionic rules
+fn uncontracted_forms(vf: &HcGreekVerbForm, steps: &[Step]) -> Vec<String> {
+    let pp_num = vf.get_pp_num() as usize;
+    let mut res = Vec::new();
+
+    for pp in steps[1].form.split(" / ") {
+        if !vf.is_contracted_verb(pp) || vf.mood == HcMood::Participle {
+            continue;
+        }
+        let full_stem = strip_accents(pp);
+
+        if vf.mood == HcMood::Infinitive {
+            let Ok(stem) = vf.strip_ending(pp_num, full_stem.to_string()) else {
+                continue;
+            };
+            let Some(endings) = vf.get_infinitive_endings(&stem) else {
+                continue;
+            };
+            for e in endings {
+                let form = format!("{}{}", stem, e);
+                if !IONIC_CONTRACTIONS
+                    .rules
+                    .iter()
+                    .any(|(from, _)| form.contains(from))
+                {
+                    res.push(accent_recessive(vf, &form));
+                }
+            }
+            continue;
+        }
+
+        let Some(endings) = vf.get_endings(pp, &full_stem) else {
+            continue;
+        };
+        for form in steps
+            .iter()
+            .filter(|s| s.explanation == "Add ending")
+            .flat_map(|s| s.form.split(" / "))
+        {
+            let Some(ending) = endings
+                .iter()
+                .flat_map(|e| e.split(','))
+                .find(|e| form.ends_with(&strip_accents(e)))
+            else {
+                continue;
+            };
+            let ionic = vf.contract_verb_with_rules(form, ending, &IONIC_CONTRACTIONS);
+            if ionic != vf.contract_verb(form, ending) {
+                res.push(ionic);
+            }
+        }
+    }
+    res
+}
+
+// Homer often leaves out the augment (λῦε for ἔλυε)
+fn unaugmented_forms(vf: &HcGreekVerbForm, forms: &[String]) -> Vec<String> {
+    if vf.mood != HcMood::Indicative
+        || (vf.tense != HcTense::Imperfect
+            && vf.tense != HcTense::Aorist
+            && vf.tense != HcTense::Pluperfect)
+    {
+        return vec![];
+    }
+
+    let mut res = Vec::new();
+    for form in forms {
+        let unaccented = strip_accents(form);
+        // εἶχον: the augment can't be told from the stem
+        if unaccented.starts_with("εἰ") {
+            continue;
+        }
+        let deaugmented = vf.deaugment(&unaccented, false);
+        if deaugmented != unaccented && !deaugmented.is_empty() {
+            res.push(vf.accent_verb(&deaugmented));
+        }
+    }
+    res
+}
+
+// unaugmented iterative past tenses: ποιέεσκον, φεύγεσκε, λύσασκε
+fn iterative_forms(vf: &HcGreekVerbForm) -> Vec<String> {
+    if vf.mood != HcMood::Indicative
+        || vf.verb.properties & PREFIXED != 0
+        || is_mi_verb(vf)
+        || vf.verb.pps[0].starts_with('-')
+    {
+        return vec![];
+    }
+    let Some(person_number) = person_number_index(vf) else {
+        return vec![];
+    };
+
+    let mut stems = Vec::new();
+    if vf.tense == HcTense::Imperfect {
+        let present = strip_accents(&vf.verb.pps[0]);
+        let stem = present
+            .strip_suffix("ομαι")
+            .or_else(|| present.strip_suffix('ω'));
+        // α- and ο-contracts form their iteratives by diectasis, which we don't generate
+        if let Some(stem) = stem.filter(|s| !s.ends_with('α') && !s.ends_with('ο')) {
+            stems.push(format!("{}εσκ", stem));
+        }
+    } else if vf.tense == HcTense::Aorist && vf.voice == HcVoice::Active {
+        for aorist in vf.verb.pps[2].split(" / ") {
+            let unaccented = strip_accents(aorist);
+            let deaugmented = vf.deaugment(&unaccented, false);
+            if let Some(stem) = deaugmented.strip_suffix('α') {
+                stems.push(format!("{}ασκ", stem));
+            } else if let Some(stem) = deaugmented.strip_suffix("ον") {
+                stems.push(format!("{}εσκ", stem));
+            }
+        }
+    }
+
+    let endings = if vf.voice == HcVoice::Active {
+        ITERATIVE_ENDINGS[0]
+    } else {
+        ITERATIVE_ENDINGS[1]
+    };
+    stems
+        .iter()
+        .map(|stem| vf.accent_verb(&format!("{}{}", stem, endings[person_number])))
+        .collect()
+}
+
+// λυέμεναι and λυέμεν beside λύειν, λυθήμεναι beside λυθῆναι
+fn epic_infinitives(vf: &HcGreekVerbForm, forms: &[String]) -> Vec<String> {
+    if vf.mood != HcMood::Infinitive {
+        return vec![];
+    }
+
+    let mut res = Vec::new();
+    for form in forms {
+        let unaccented = strip_accents(form);
+        let long_form = if vf.voice == HcVoice::Active
+            && vf.tense != HcTense::Perfect
+            && !vf.is_contracted_verb(&vf.verb.pps[0])
+        {
+            if is_mi_verb(vf) && vf.tense == HcTense::Present {
+                unaccented
+                    .strip_suffix("ναι")
+                    .map(|s| format!("{}μεναι", s))
+            } else {
+                unaccented
+                    .strip_suffix("ειν")
+                    .map(|s| format!("{}εμεναι", s))
+            }
+        } else if vf.tense == HcTense::Aorist && vf.voice == HcVoice::Passive {
+            unaccented
+                .strip_suffix("ηναι")
+                .map(|s| format!("{}ημεναι", s))
+        } else {
+            None
+        };
+
+        if let Some(long_form) = long_form {
+            let accented = accent_recessive(vf, &long_form);
+            // the short form keeps the accent of the long one (δόμεναι, δόμεν)
+            if let Some(short_form) = accented.strip_suffix("αι") {
+                res.push(accented.to_string());
+                res.push(short_form.to_string());
+            }
+        }
+    }
+    res
+}
+
+// ἐθέλωμι and ἐθέλῃσι beside ἐθέλω and ἐθέλῃ; short vowel λύσομεν beside λύσωμεν
+fn epic_subjunctives(vf: &HcGreekVerbForm, forms: &[String]) -> Vec<String> {
+    if vf.mood != HcMood::Subjunctive
+        || vf.voice == HcVoice::Passive
+        || (vf.tense != HcTense::Present && vf.tense != HcTense::Aorist)
+        || is_mi_verb(vf)
+        || vf.is_contracted_verb(&vf.verb.pps[0])
+    {
+        return vec![];
+    }
+
+    let sigmatic_aorist = vf.tense == HcTense::Aorist
+        && vf.verb.pps[2]
+            .split(" / ")
+            .any(|pp| pp.ends_with('α') || pp.ends_with("άμην"));
+
+    let mut res = Vec::new();
+    for form in forms {
+        let unaccented = strip_accents(form);
+        if vf.voice == HcVoice::Active && vf.number == Some(HcNumber::Singular) {
+            if vf.person == Some(HcPerson::First) && unaccented.ends_with('ω') {
+                res.push(vf.accent_verb(&format!("{}μι", unaccented)));
+            } else if vf.person == Some(HcPerson::Third) && unaccented.ends_with('ῃ') {
+                res.push(vf.accent_verb(&format!("{}σι(ν)", unaccented)));
+            }
+        }
+        if sigmatic_aorist {
+            for (long, short) in SHORT_VOWEL_SUBJUNCTIVE {
+                if let Some(stem) = unaccented.strip_suffix(long) {
+                    res.push(vf.accent_verb(&format!("{}{}", stem, short)));
+                    break;
+                }
+            }
+        }
+    }
+    res
+}
+
+fn apply_koine(vf: &HcGreekVerbForm, steps: &mut Vec<Step>) -> Result<(), HcFormError> {
+    let dialect = HcDialect::Koine;
+    let mut forms = last_forms(steps);
+
+    if vf.mood == HcMood::Optative {
+        forms.retain(|f| KOINE_OPTATIVES.contains(&f.as_str()));
+        if forms.is_empty() {
+            return Err(HcFormError::DoesNotExist);
+        }
+        push_dialect_step(
+            steps,
+            forms.clone(),
+            "Keep only fossilized optative",
+            dialect,
+        );
+    }
+
+    let first_aorist = first_aorist_endings(vf, &forms);
+    if !first_aorist.is_empty() {
+        forms.extend(first_aorist);
+        push_dialect_step(steps, forms.clone(), "Add first aorist ending", dialect);
+    }
+
+    let sigma_alpha_nu = third_plural_in_san(vf, steps, &forms);
+    if !sigma_alpha_nu.is_empty() {
+        forms.extend(sigma_alpha_nu);
+        push_dialect_step(steps, forms.clone(), "Add third plural in -σαν", dialect);
+    }
+
+    if forms.iter().any(|f| f.contains("ττ")) {
+        let forms = forms.iter().map(|f| f.replace("ττ", "σσ")).collect();
+        push_dialect_step(steps, forms, "Write -σσ- for -ττ-", dialect);
+    }
+    Ok(())
+}
+
+fn is_second_aorist(vf: &HcGreekVerbForm) -> bool {
+    vf.tense == HcTense::Aorist
+        && vf.voice == HcVoice::Active
+        && vf.verb.pps[2].split(" / ").any(|pp| pp.ends_with("ον"))
+}
+
+// εἶπαν, ἦλθαν beside εἶπον, ἦλθον
+fn first_aorist_endings(vf: &HcGreekVerbForm, forms: &[String]) -> Vec<String> {
+    let Some(person_number) = person_number_index(vf) else {
+        return vec![];
+    };
+    if vf.mood != HcMood::Indicative || !is_second_aorist(vf) {
+        return vec![];
+    }
+
+    let (second, first) = KOINE_SECOND_AORIST[person_number];
+    if second == first {
+        return vec![];
+    }
+    forms
+        .iter()
+        .filter_map(|form| {
+            let stem = strip_accents(form).strip_suffix(second)?.to_string();
+            Some(vf.accent_verb(&format!("{}{}", stem, first)))
+        })
+        .collect()
+}
+
+// ἐλάβοσαν, εἴχοσαν, ἐποιοῦσαν beside ἔλαβον, εἶχον, ἐποίουν
+fn third_plural_in_san(vf: &HcGreekVerbForm, steps: &[Step], forms: &[String]) -> Vec<String> {
+    if vf.mood != HcMood::Indicative
+        || vf.voice != HcVoice::Active
+        || vf.person != Some(HcPerson::Third)
+        || vf.number != Some(HcNumber::Plural)
+        || is_mi_verb(vf)
+        || !(vf.tense == HcTense::Imperfect || is_second_aorist(vf))
+    {
+        return vec![];
+    }
+
+    if steps[1]
+        .form
+        .split(" / ")
+        .any(|pp| vf.is_contracted_verb(pp))
+    {
+        // add -σαν to the uncontracted form, then contract it again
+        return steps
+            .iter()
+            .filter(|s| s.explanation == "Add ending")
+            .flat_map(|s| s.form.split(" / "))
+            .filter_map(|form| form.strip_suffix("ον"))
+            .map(|stem| vf.contract_verb(&format!("{}οσαν", stem), "οσαν"))
+            .collect();
+    }
+
+    forms
+        .iter()
+        .filter_map(|form| {
+            // only the thematic -ον, not the first aorist -αν added above
+            let stem = strip_accents(form).strip_suffix("ον")?.to_string();
+            Some(vf.accent_verb(&format!("{}οσαν", stem)))
+        })
+        .collect()
+}
@@ -0,0 +1,328 @@
+//! Wrong options for multiple-choice questions, made from the mistakes beginners make.
+//!
+//! Each distractor is what the engine produces when one step of the derivation goes wrong:
+//!
+//! - `UnassimilatedConsonants`: a finite consonant-stem perfect middle whose stem and
+//!   ending are joined without assimilation (γέγραφμαι for γέγραμμαι).
+//! - `RecessiveAccent`: a contract form accented as though it were uncontracted (ποίουμεν
+//!   for ποιοῦμεν).
+//! - `MissingAugment`: a past indicative made without the augment step (παίδευον for
+//!   ἐπαίδευον).
+//! - `WrongPrincipalPart`: the stem of another principal part with the form's own ending
+//!   (ἐπαίδευα for ἐπαίδευσα).
+//! - `WrongEnding`: the form's own stem with the ending of the same person and number in
+//!   another tense (ἐπαίδευσον for ἐπαίδευσα).
+//!
+//! The mistakes are taken in that order, one of each in turn, so that the options differ
+//! in kind.  Only finite forms have distractors.  A distractor is never a form of the verb
+//! in the same voice and mood, nor a spelling of one.
+
+use super::*;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+pub enum HcMistake {
+    UnassimilatedConsonants,
+    RecessiveAccent,
+    MissingAugment,
+    WrongPrincipalPart,
+    WrongEnding,
+}
+
+impl HcMistake {
+    pub fn value(&self) -> &str {
+        match self {
+            HcMistake::UnassimilatedConsonants => "UnassimilatedConsonants",
+            HcMistake::RecessiveAccent => "RecessiveAccent",
+            HcMistake::MissingAugment => "MissingAugment",
+            HcMistake::WrongPrincipalPart => "WrongPrincipalPart",
+            HcMistake::WrongEnding => "WrongEnding",
+        }
+    }
+}
+
+impl fmt::Display for HcMistake {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HcMistake::UnassimilatedConsonants => "the consonants were not assimilated",
+            HcMistake::RecessiveAccent => "the contract form was accented recessively",
+            HcMistake::MissingAugment => "the augment was left out",
+            HcMistake::WrongPrincipalPart => "the form was made from the wrong principal part",
+            HcMistake::WrongEnding => "the right stem was given the wrong ending",
+        })
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcDistractor {
+    /// with a movable ν written (ν), as get_form() writes it
+    pub form: String,
+    pub mistake: HcMistake,
+}
+
+/// up to `n` wrong options for the form, the same each time; an error if the form itself
+/// can't be made
+pub fn distractors(vf: &HcGreekVerbForm, n: usize) -> Result<Vec<HcDistractor>, HcFormError> {
+    let alternates = vf.get_form_alternates(false)?;
+    let correct = match alternates.first() {
+        Some(a) if a.form != BLANK => a.form.clone(),
+        _ => {
+            return Err(HcFormError::IllegalForm {
+                reason: "the form is left blank",
+            })
+        }
+    };
+    if matches!(vf.mood, HcMood::Infinitive | HcMood::Participle) {
+        return Ok(vec![]);
+    }
+    let mut seen = paradigm(vf);
+    seen.extend(alternates.iter().flat_map(|a| a.spellings()));
+
+    let mut candidates = [
+        (
+            HcMistake::UnassimilatedConsonants,
+            unassimilated(vf, &correct),
+        ),
+        (HcMistake::RecessiveAccent, recessive_accent(vf, &correct)),
+        (HcMistake::MissingAugment, missing_augment(vf)),
+        (HcMistake::WrongPrincipalPart, wrong_principal_part(vf)),
+        (HcMistake::WrongEnding, wrong_ending(vf)),
+    ]
+    .map(|(mistake, forms)| (mistake, forms.into_iter()));
+
+    let mut res = Vec::new();
+    loop {
+        let mut more = false;
+        for (mistake, forms) in candidates.iter_mut() {
+            if res.len() == n {
+                return Ok(res);
+            }
+            // the first of this kind which is new
+            if let Some(form) = forms.find(|f| {
+                let spellings = HcAlternate::new(f.clone(), 0, 0).spellings();
+                !f.is_empty() && f != BLANK && spellings.iter().all(|s| !seen.contains(s))
+            }) {
+                seen.extend(HcAlternate::new(form.clone(), 0, 0).spellings());
+                res.push(HcDistractor {
+                    form,
+                    mistake: *mistake,
+                });
+                more = true;
+            }
+        }
+        if !more {
+            return Ok(res);
+        }
+    }
+}
+
+const TENSES: [HcTense; 6] = [
+    HcTense::Present,
+    HcTense::Imperfect,
+    HcTense::Future,
+    HcTense::Aorist,
+    HcTense::Perfect,
+    HcTense::Pluperfect,
+];
+
+fn strip_accents(form: &str) -> String {
+    hgk_strip_diacritics(form, HGK_ACUTE | HGK_CIRCUMFLEX | HGK_GRAVE)
+}
+
+// the spellings of every finite form of the verb in the voice and mood of `vf`
+fn paradigm(vf: &HcGreekVerbForm) -> HashSet<String> {
+    let mut forms = HashSet::new();
+    for tense in TENSES {
+        for number in [HcNumber::Singular, HcNumber::Plural] {
+            for person in [HcPerson::First, HcPerson::Second, HcPerson::Third] {
+                let cell = HcGreekVerbForm {
+                    tense,
+                    person: Some(person),
+                    number: Some(number),
+                    ..vf.clone()
+                };
+                if let Ok(alternates) = cell.get_form_alternates(false) {
+                    forms.extend(alternates.iter().flat_map(|a| a.spellings()));
+                }
+            }
+        }
+    }
+    forms
+}
+
+// the first form of a principal part, without its accent; None if it is blank
+fn principal_part(vf: &HcGreekVerbForm, pp_num: usize) -> Option<&str> {
+    let pp = vf.verb.pps.get(pp_num - 1)?.split(" / ").next()?;
+    (pp != BLANK && !pp.is_empty()).then_some(pp)
+}
+
+// the augment step of get_form(): the past indicatives have an augment, which the third and
+// sixth principal parts already have, and the other tenses have none
+fn augment(vf: &HcGreekVerbForm, pp_num: usize, pp: &str) -> String {
+    let unaccented = strip_accents(pp);
+    let has_augment = pp_num == 3 || pp_num == 6;
+    let wants_augment = vf.mood == HcMood::Indicative
+        && matches!(
+            vf.tense,
+            HcTense::Imperfect | HcTense::Aorist | HcTense::Pluperfect
+        );
+    match (has_augment, wants_augment) {
+        (false, true) => vf.add_augment(&unaccented, false),
+        (true, false) => vf.deaugment(&unaccented, false),
+        _ => unaccented,
+    }
+}
+
+// the rest of get_form()'s steps for a finite form: the principal part's ending taken off
+// `full_stem`, the first ending `ending_vf` has added, and the accent placed
+fn derive_finite(
+    vf: &HcGreekVerbForm,
+    pp_num: usize,
+    pp: &str,
+    full_stem: &str,
+    ending_vf: &HcGreekVerbForm,
+) -> Option<String> {
+    let ending = *ending_vf.get_endings(pp, full_stem)?.first()?;
+    let stem = vf.strip_ending(pp_num, full_stem.to_string()).ok()?;
+    if stem.is_empty() {
+        return None;
+    }
+    let form = vf.add_ending(pp, full_stem, &stem, ending, false).ok()?;
+    let form = if hgk_has_diacritics(&form, HGK_ACUTE | HGK_CIRCUMFLEX | HGK_GRAVE) {
+        form
+    } else {
+        vf.accent_verb(&form)
+    };
+    Some(if vf.is_contracted_verb(pp) {
+        vf.contract_verb(&form, ending)
+    } else {
+        form
+    })
+}
+// the stem and ending as decomposed, joined as they are without contract_consonants()
+fn unassimilated(vf: &HcGreekVerbForm, correct: &str) -> Vec<String> {
+    if !matches!(vf.tense, HcTense::Perfect | HcTense::Pluperfect)
+        || vf.voice == HcVoice::Active
+        || matches!(vf.mood, HcMood::Infinitive | HcMood::Participle)
+        || !vf.get_pp().is_some_and(|pp| {
+            pp.split(" / ")
+                .any(|p| vf.is_consonant_stem(&strip_accents(p)))
+        })
+    {
+        return vec![];
+    }
+    let Some(decomposed) = vf
+        .get_form_alternates(true)
+        .ok()
+        .and_then(|a| a.into_iter().next())
+    else {
+        return vec![];
+    };
+    let separator = format!(" {} ", SEPARATOR);
+    let mut parts = decomposed.form.rsplit(separator.as_str());
+    let (Some(ending), Some(stem)) = (parts.next(), parts.next()) else {
+        return vec![];
+    };
+
+    let unaccented = strip_accents(correct);
+    CONSONANT_REPLACEMENTS
+        .iter()
+        .filter(|r| stem.ends_with(r[0]) && ending.starts_with(r[1]))
+        .take(1)
+        .filter_map(|r| {
+            let assimilated = format!("{}{}", r[0], ending).replacen(r[2], r[3], 1);
+            let head = unaccented.strip_suffix(assimilated.as_str())?;
+            Some(vf.accent_verb(&format!("{}{}{}", head, r[0], ending)))
+        })
+        .collect()
+}
+
+// the accent placed by accent_verb() rather than accent_verb_contracted()
+fn recessive_accent(vf: &HcGreekVerbForm, correct: &str) -> Vec<String> {
+    if matches!(vf.mood, HcMood::Infinitive | HcMood::Participle)
+        || !vf.get_pp().is_some_and(|pp| vf.is_contracted_verb(&pp))
+    {
+        return vec![];
+    }
+    vec![vf.accent_verb(&strip_accents(correct))]
+}
+
+// the form made without the augment step, from the principal part with its own augment
+// taken off
+fn missing_augment(vf: &HcGreekVerbForm) -> Vec<String> {
+    if vf.mood != HcMood::Indicative
+        || !matches!(
+            vf.tense,
+            HcTense::Imperfect | HcTense::Aorist | HcTense::Pluperfect
+        )
+    {
+        return vec![];
+    }
+    let pp_num = vf.get_pp_num() as usize;
+    let Some(pp) = principal_part(vf, pp_num) else {
+        return vec![];
+    };
+    let full_stem = if pp_num == 3 || pp_num == 6 {
+        vf.deaugment(&strip_accents(pp), false)
+    } else {
+        strip_accents(pp)
+    };
+    derive_finite(vf, pp_num, pp, &full_stem, vf)
+        .into_iter()
+        .collect()
+}
+
+// the principal parts of the other tenses in place of the form's own, the past indicatives
+// taking each other's
+fn wrong_principal_part(vf: &HcGreekVerbForm) -> Vec<String> {
+    let tenses: &[HcTense] = if vf.mood == HcMood::Indicative
+        && matches!(
+            vf.tense,
+            HcTense::Imperfect | HcTense::Aorist | HcTense::Pluperfect
+        ) {
+        &[HcTense::Imperfect, HcTense::Aorist, HcTense::Pluperfect]
+    } else {
+        &[
+            HcTense::Present,
+            HcTense::Future,
+            HcTense::Aorist,
+            HcTense::Perfect,
+        ]
+    };
+    let own = vf.get_pp_num();
+    tenses
+        .iter()
+        .map(|tense| {
+            HcGreekVerbForm {
+                tense: *tense,
+                ..vf.clone()
+            }
+            .get_pp_num()
+        })
+        .filter(|pp| *pp != own)
+        .filter_map(|pp| {
+            let pp_num = pp as usize;
+            let pp = principal_part(vf, pp_num)?;
+            derive_finite(vf, pp_num, pp, &augment(vf, pp_num, pp), vf)
+        })
+        .collect()
+}
+
+// the form's own stem with the endings of the same person and number in the other tenses
+fn wrong_ending(vf: &HcGreekVerbForm) -> Vec<String> {
+    let pp_num = vf.get_pp_num() as usize;
+    let Some(pp) = principal_part(vf, pp_num) else {
+        return vec![];
+    };
+    let full_stem = augment(vf, pp_num, pp);
+    TENSES
+        .into_iter()
+        .filter(|tense| *tense != vf.tense)
+        .filter_map(|tense| {
+            let ending_vf = HcGreekVerbForm {
+                tense,
+                ..vf.clone()
+            };
+            derive_finite(vf, pp_num, pp, &full_stem, &ending_vf)
+        })
+        .collect()
+}
@@ -0,0 +1,291 @@
+use super::*;
+
+// Smyth 691-697: the perfect subjunctive and optative are usually periphrastic and the
+// perfect imperative is rare, so H&Q leaves these out.  These forms are only generated
+// when HcFormOptions::extended_perfect is set and are marked as beyond the H&Q syllabus.
+
+pub static BEYOND_HQ_SYLLABUS: &str = "beyond H&Q syllabus";
+
+// perfect middle/passive imperative: λέλυσο, λελύσθω, λέλυσθε, λελύσθων
+static PERFECT_MIDPASS_IMPERATIVE: &[&str; 6] = &["", "σο", "σθω", "", "σθε", "σθων"];
+// second perfect imperative: ἕσταθι, ἑστάτω, ἕστατε, ἑστάντων
+static SECOND_PERFECT_IMPERATIVE: &[&str; 6] = &["", "αθι", "ατω", "", "ατε", "αντων"];
+
+impl HcGreekVerbForm {
+    /// true if this form, or one of its alternates, is only generated with
+    /// HcFormOptions::extended_perfect
+    pub fn is_beyond_hq_syllabus(&self) -> bool {
+        is_extended_perfect_form(self) || is_second_perfect_participle(self)
+    }
+}
+
+// ἕστηκα (and compounds) and τέθνηκα have μι-style second perfects
+fn has_second_perfect(vf: &HcGreekVerbForm) -> bool {
+    vf.verb.pp(3).ends_with("στηκα") || vf.verb.pp(0).ends_with("θνῄσκω")
+}
+
+// the participles given a second alternate by add_second_perfect_participles()
+fn is_second_perfect_participle(vf: &HcGreekVerbForm) -> bool {
+    vf.tense == HcTense::Perfect
+        && vf.voice == HcVoice::Active
+        && vf.mood == HcMood::Participle
+        && has_second_perfect(vf)
+}
+
+pub fn is_extended_perfect_form(vf: &HcGreekVerbForm) -> bool {
+    vf.tense == HcTense::Perfect
+        && (vf.mood == HcMood::Subjunctive
+            || vf.mood == HcMood::Optative
+            || vf.mood == HcMood::Imperative)
+        && !vf.verb.pp(0).ends_with("δα") // οἶδα is already in the syllabus
+}
+
+fn mark_beyond_syllabus(steps: &mut [Step]) {
+    if let Some(last) = steps.last_mut() {
+        last.explanation = format!("{} ({})", last.explanation, BEYOND_HQ_SYLLABUS);
+    }
+}
+
+pub fn get_extended_perfect(
+    vf: &HcGreekVerbForm,
+    decompose: bool,
+) -> Result<Vec<Step>, HcFormError> {
+    if vf.verb.pps.len() != 6 {
+        return Err(HcFormError::InternalError(
+            HcInternalError::PrincipalPartCount(vf.verb.pps.len()),
+        ));
+    }
+    if vf.person.is_none() || vf.number.is_none() || vf.gender.is_some() || vf.case.is_some() {
+        return Err(HcFormError::IllegalForm {
+            reason: "a finite form has a person and number, but no gender or case",
+        });
+    }
+    if vf.mood == HcMood::Imperative && vf.person == Some(HcPerson::First) {
+        return Err(HcFormError::IllegalForm {
+            reason: "the imperative has no first person",
+        });
+    }
+    if vf.number == Some(HcNumber::Dual) {
+        return Err(HcFormError::NotImplemented);
+    }
+
+    let mut steps = vec![Step {
+        form: vf.verb.pps.join(", "),
+        explanation: "Principal Parts".to_string(),
+    }];
+
+    let pp = vf.get_pp_num();
+    let pp_num = pp as usize;
+    let principal_part = &vf.verb.pps[pp_num - 1];
+    steps.push(Step {
+        form: principal_part.to_string(),
+        explanation: "Choose Principal Part".to_string(),
+    });
+
+    if principal_part == BLANK {
+        return Err(HcFormError::BlankPrincipalPartForForm(pp));
+    }
+    if !vf.is_legal_deponent(principal_part) {
+        return Err(HcFormError::Deponent {
+            pp,
+            principal_part: principal_part.to_string(),
+            voice: vf.voice,
+        });
+    }
+
+    let person_number: usize = match (vf.person, vf.number) {
+        (Some(HcPerson::First), Some(HcNumber::Singular)) => 0,
+        (Some(HcPerson::Second), Some(HcNumber::Singular)) => 1,
+        (Some(HcPerson::Third), Some(HcNumber::Singular)) => 2,
+        (Some(HcPerson::First), Some(HcNumber::Plural)) => 3,
+        (Some(HcPerson::Second), Some(HcNumber::Plural)) => 4,
+        (Some(HcPerson::Third), Some(HcNumber::Plural)) => 5,
+        _ => {
+            return Err(HcFormError::InternalError(HcInternalError::NoEndings {
+                principal_part: principal_part.to_string(),
+            }))
+        }
+    };
+
+    // (stem, ending, is consonant stem); the ending keeps its accent if it has one
+    let mut stem_endings: Vec<(String, String, bool)> = Vec::new();
+
+    for full_stem_with_accent in principal_part.split(" / ") {
+        let full_stem = hgk_strip_diacritics(
+            full_stem_with_accent,
+            HGK_ACUTE | HGK_CIRCUMFLEX | HGK_GRAVE,
+        );
+
+        if vf.voice == HcVoice::Active {
+            let stem = match vf.strip_ending(pp_num, full_stem.to_string()) {
+                Ok(res) => res,
+                Err(_) => {
+                    return Err(HcFormError::UnexpectedPrincipalPartEnding {
+                        pp,
+                        principal_part: full_stem_with_accent.to_string(),
+                    })
+                }
+            };
+            let second_perfect_stem = if has_second_perfect(vf) {
+                stem.strip_suffix("ηκ").map(|s| s.to_string())
+            } else {
+                None
+            };
+
+            match vf.mood {
+                HcMood::Subjunctive => {
+                    // ἑστῶ, ἑστῇς beside ἑστήκω
+                    if let Some(s) = &second_perfect_stem {
+                        if full_stem.ends_with("στηκα") {
+                            let e = ENDINGS[HcEndings::AoristPassiveSubj as usize][person_number];
+                            stem_endings.push((s.to_string(), e.to_string(), false));
+                        }
+                    }
+                    let e = ENDINGS[HcEndings::PresentActiveSubj as usize][person_number];
+                    stem_endings.push((stem.to_string(), e.to_string(), false));
+                }
+                HcMood::Optative => {
+                    if let Some(s) = &second_perfect_stem {
+                        // ἑσταίην, τεθναίην
+                        for e in ENDINGS[HcEndings::PresentActiveOptMi as usize][person_number]
+                            .split(',')
+                        {
+                            stem_endings.push((format!("{}α", s), e.to_string(), false));
+                        }
+                    } else {
+                        // λελύκοιμι or λελυκοίην
+                        for e in ENDINGS[HcEndings::PresentActiveOptEContracted as usize]
+                            [person_number]
+                            .split(',')
+                        {
+                            stem_endings.push((stem.to_string(), e.to_string(), false));
+                        }
+                    }
+                }
+                _ => {
+                    // an active perfect imperative is only found in perfects with present meaning
+                    match &second_perfect_stem {
+                        Some(s) => stem_endings.push((
+                            s.to_string(),
+                            SECOND_PERFECT_IMPERATIVE[person_number].to_string(),
+                            false,
+                        )),
+                        None => return Err(HcFormError::DoesNotExist),
+                    }
+                }
+            }
+        } else {
+            // perfect middle/passive subjunctive and optative are periphrastic (λελυμένος ὦ)
+            if vf.mood != HcMood::Imperative {
+                return Err(HcFormError::DoesNotExist);
+            }
+            let stem = match vf.strip_ending(pp_num, full_stem.to_string()) {
+                Ok(res) => res,
+                Err(_) => {
+                    return Err(HcFormError::UnexpectedPrincipalPartEnding {
+                        pp,
+                        principal_part: full_stem_with_accent.to_string(),
+                    })
+                }
+            };
+            stem_endings.push((
+                stem,
+                PERFECT_MIDPASS_IMPERATIVE[person_number].to_string(),
+                vf.is_consonant_stem(full_stem_with_accent),
+            ));
+        }
+    }
+
+    let mut add_ending_collector = Vec::new();
+    let mut add_accent_collector = Vec::new();
+    for (stem, ending, is_consonant_stem) in stem_endings {
+        let unaccented_ending =
+            hgk_strip_diacritics(&ending, HGK_ACUTE | HGK_CIRCUMFLEX | HGK_GRAVE);
+
+        if is_consonant_stem {
+            // the 3rd plural is periphrastic in the indicative, but not in the imperative
+            let probe = HcGreekVerbForm {
+                number: Some(HcNumber::Singular),
+                ..vf.clone()
+            };
+            let form = probe.contract_consonants(&stem, &unaccented_ending, decompose);
+            if decompose {
+                add_ending_collector.push(vf.separate_prefix(&form));
+            } else {
+                add_ending_collector.push(form.to_string());
+                add_accent_collector.push(vf.accent_verb(&form));
+            }
+            continue;
+        }
+
+        if decompose {
+            add_ending_collector
+                .push(vf.separate_prefix(&format!("{} {} {}", stem, SEPARATOR, unaccented_ending)));
+        } else {
+            let form = format!("{}{}", stem, ending);
+            add_ending_collector.push(form.to_string());
+            if hgk_has_diacritics(&form, HGK_ACUTE | HGK_CIRCUMFLEX | HGK_GRAVE) {
+                add_accent_collector.push(form);
+            } else {
+                add_accent_collector.push(vf.accent_verb(&form));
+            }
+        }
+    }
+
+    let mut unique = HashSet::new();
+    add_ending_collector.retain(|item| unique.insert(item.clone()));
+    steps.push(Step {
+        form: add_ending_collector.join(" / "),
+        explanation: "Add ending".to_string(),
+    });
+
+    if !decompose {
+        let mut unique = HashSet::new();
+        add_accent_collector.retain(|item| unique.insert(item.clone()));
+        steps.push(Step {
+            form: add_accent_collector.join(" / "),
+            explanation: "Accent verb".to_string(),
+        });
+    }
+
+    mark_beyond_syllabus(&mut steps);
+    Ok(steps)
+}
+
+// adds ἑστηκώς beside ἑστώς and τεθνεώς beside τεθνηκώς
+pub fn add_second_perfect_participles(vf: &HcGreekVerbForm, steps: &mut [Step]) {
+    if !is_second_perfect_participle(vf) {
+        return;
+    }
+
+    let principal_part = &vf.verb.pps[3];
+    let full_stem = hgk_strip_diacritics(principal_part, HGK_ACUTE | HGK_CIRCUMFLEX | HGK_GRAVE);
+    // get_participle_endings() gives a perfect in -στηκα the endings of ἑστώς and any other
+    // those of a κ-perfect, so each takes the other's here
+    let alt = if full_stem.ends_with("στηκα") {
+        full_stem
+            .strip_suffix('α')
+            .zip(vf.get_participle_endings(""))
+            .map(|(stem, endings)| format!("{}{}", stem, endings[0]))
+    } else {
+        full_stem
+            .strip_suffix("ηκα")
+            .zip(vf.get_participle_endings("στηκα"))
+            .map(|(stem, endings)| format!("{}ε{}", stem, endings[0]))
+    }
+    // an ending such as -υιῶν has its accent already
+    .map(|ptc| {
+        if hgk_has_diacritics(&ptc, HGK_ACUTE | HGK_CIRCUMFLEX | HGK_GRAVE) {
+            ptc
+        } else {
+            vf.accent_participle(principal_part, &ptc, &full_stem)
+        }
+    });
+
+    if let (Some(alt), Some(last)) = (alt, steps.last_mut()) {
+        if !last.form.split(" / ").any(|f| f == alt) {
+            last.form = format!("{} / {}", last.form, alt);
+            last.explanation = format!("{} ({})", last.explanation, BEYOND_HQ_SYLLABUS);
+        }
+    }
+}
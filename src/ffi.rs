@@ -0,0 +1,241 @@
+//! C ABI for embedding in native apps.  The C header is generated from this file by
+//! build.rs into `OUT_DIR` when the `ffi` feature is enabled; tests/ffi.rs checks that the
+//! copy in include/hoplite_verbs.h is up to date.
+//!
+//! Verbs and form results are opaque pointers which must be released with their free
+//! functions.  Strings passed in are NUL-terminated UTF-8; strings handed back are owned
+//! copies which must be released with `hc_string_free`.  Parameters are the integer codes
+//! of `to_i16`/`from_i16`, with -1 for no person or number (infinitives and participles)
+//! and for no gender or case (all but participles).
+
+use super::*;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+/// Result codes, the first ones mirroring HcFormError.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HcErrorCode {
+    Ok = 0,
+    InternalError = 1,
+    BlankPrincipalPart = 2,
+    InvalidPrincipalPart = 3,
+    DeponentNoFormForVoice = 4,
+    IllegalForm = 5,
+    DoesNotExist = 6,
+    NoFormForUnit = 7,
+    NotImplemented = 8,
+    /// a null pointer or a parameter code out of range
+    InvalidArgument = 9,
+}
+
+impl From<HcFormError> for HcErrorCode {
+    fn from(e: HcFormError) -> HcErrorCode {
+        match e {
+            HcFormError::InternalError(_) => HcErrorCode::InternalError,
+            HcFormError::BlankPrincipalPartForForm(_) => HcErrorCode::BlankPrincipalPart,
+            HcFormError::UnexpectedPrincipalPartEnding { .. } => HcErrorCode::InvalidPrincipalPart,
+            HcFormError::Deponent { .. } => HcErrorCode::DeponentNoFormForVoice,
+            HcFormError::IllegalForm { .. } => HcErrorCode::IllegalForm,
+            HcFormError::DoesNotExist => HcErrorCode::DoesNotExist,
+            HcFormError::NotAvailableInUnit => HcErrorCode::NoFormForUnit,
+            HcFormError::NotImplemented => HcErrorCode::NotImplemented,
+        }
+    }
+}
+
+/// A verb created by `hc_verb_new`.
+pub struct HcVerb {
+    verb: Arc<HcGreekVerb>,
+}
+
+/// The steps of a form created by `hc_get_form`, the form itself being the last step.
+pub struct HcFormResult {
+    steps: Vec<Step>,
+}
+
+fn to_c_string(s: &str) -> *mut c_char {
+    // forms never contain NUL
+    CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn code_in_range(code: i16, max: i16) -> bool {
+    (0..=max).contains(&code)
+}
+
+/// Creates a verb from a line of pp.txt: the six comma-separated principal parts,
+/// optionally followed by %unit%properties.  Returns NULL if the line is not valid.
+///
+/// # Safety
+/// `pps` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn hc_verb_new(id: u32, pps: *const c_char) -> *mut HcVerb {
+    if pps.is_null() {
+        return ptr::null_mut();
+    }
+    let Ok(pps) = CStr::from_ptr(pps).to_str() else {
+        return ptr::null_mut();
+    };
+    catch_unwind(|| HcGreekVerb::from_string_with_properties(id, pps))
+        .ok()
+        .flatten()
+        .map_or(ptr::null_mut(), |verb| {
+            Box::into_raw(Box::new(HcVerb {
+                verb: Arc::new(verb),
+            }))
+        })
+}
+
+/// # Safety
+/// `verb` must be NULL or a pointer returned by `hc_verb_new`, not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn hc_verb_free(verb: *mut HcVerb) {
+    if !verb.is_null() {
+        drop(Box::from_raw(verb));
+    }
+}
+
+/// Generates a form, storing its steps in `*out` on success (HC_ERROR_CODE_OK).
+/// `*out` is set to NULL on failure.
+///
+/// # Safety
+/// `verb` must be a pointer returned by `hc_verb_new`, and `out` a valid pointer.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn hc_get_form(
+    verb: *const HcVerb,
+    person: i16,
+    number: i16,
+    tense: i16,
+    voice: i16,
+    mood: i16,
+    gender: i16,
+    case: i16,
+    decompose: bool,
+    out: *mut *mut HcFormResult,
+) -> HcErrorCode {
+    if out.is_null() {
+        return HcErrorCode::InvalidArgument;
+    }
+    *out = ptr::null_mut();
+    if !(person == -1 || code_in_range(person, 2))
+        || !(number == -1 || code_in_range(number, 2))
+        || !code_in_range(tense, 5)
+        || !code_in_range(voice, 2)
+        || !code_in_range(mood, 5)
+        || !(gender == -1 || code_in_range(gender, 2))
+        || !(case == -1 || code_in_range(case, 4))
+    {
+        return HcErrorCode::InvalidArgument;
+    }
+
+    let Some(verb) = verb.as_ref() else {
+        return HcErrorCode::InvalidArgument;
+    };
+    let vf = HcGreekVerbForm {
+        verb: verb.verb.clone(),
+        person: (person != -1).then(|| HcPerson::from_i16(person)),
+        number: (number != -1).then(|| HcNumber::from_i16(number)),
+        tense: HcTense::from_i16(tense),
+        voice: HcVoice::from_i16(voice),
+        mood: HcMood::from_i16(mood),
+        gender: (gender != -1).then(|| HcGender::from_i16(gender)),
+        case: (case != -1).then(|| HcCase::from_i16(case)),
+    };
+    match catch_unwind(AssertUnwindSafe(|| vf.get_form(decompose))) {
+        Ok(Ok(steps)) => {
+            *out = Box::into_raw(Box::new(HcFormResult { steps }));
+            HcErrorCode::Ok
+        }
+        Ok(Err(e)) => e.into(),
+        Err(_) => HcErrorCode::InternalError,
+    }
+}
+
+/// # Safety
+/// `result` must be NULL or a pointer set by `hc_get_form`, not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn hc_form_result_free(result: *mut HcFormResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result));
+    }
+}
+
+/// # Safety
+/// `result` must be NULL or a pointer set by `hc_get_form`.
+#[no_mangle]
+pub unsafe extern "C" fn hc_form_result_step_count(result: *const HcFormResult) -> usize {
+    result.as_ref().map_or(0, |r| r.steps.len())
+}
+
+/// The final form, to be released with `hc_string_free`.
+///
+/// # Safety
+/// `result` must be NULL or a pointer set by `hc_get_form`.
+#[no_mangle]
+pub unsafe extern "C" fn hc_form_result_form(result: *const HcFormResult) -> *mut c_char {
+    match result.as_ref().and_then(|r| r.steps.last()) {
+        Some(step) => to_c_string(&step.form),
+        None => ptr::null_mut(),
+    }
+}
+
+/// The form of step `index`, to be released with `hc_string_free`; NULL if out of range.
+///
+/// # Safety
+/// `result` must be NULL or a pointer set by `hc_get_form`.
+#[no_mangle]
+pub unsafe extern "C" fn hc_form_result_step_form(
+    result: *const HcFormResult,
+    index: usize,
+) -> *mut c_char {
+    match result.as_ref().and_then(|r| r.steps.get(index)) {
+        Some(step) => to_c_string(&step.form),
+        None => ptr::null_mut(),
+    }
+}
+
+/// The explanation of step `index`, to be released with `hc_string_free`; NULL if out of
+/// range.
+///
+/// # Safety
+/// `result` must be NULL or a pointer set by `hc_get_form`.
+#[no_mangle]
+pub unsafe extern "C" fn hc_form_result_step_explanation(
+    result: *const HcFormResult,
+    index: usize,
+) -> *mut c_char {
+    match result.as_ref().and_then(|r| r.steps.get(index)) {
+        Some(step) => to_c_string(&step.explanation),
+        None => ptr::null_mut(),
+    }
+}
+
+/// # Safety
+/// `s` must be NULL or a string returned by this library, not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn hc_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// The name of an error code, e.g. "BlankPrincipalPart".  The string is static and must
+/// not be freed.
+#[no_mangle]
+pub extern "C" fn hc_error_name(code: HcErrorCode) -> *const c_char {
+    let name: &'static CStr = match code {
+        HcErrorCode::Ok => c"Ok",
+        HcErrorCode::InternalError => c"InternalError",
+        HcErrorCode::BlankPrincipalPart => c"BlankPrincipalPart",
+        HcErrorCode::InvalidPrincipalPart => c"InvalidPrincipalPart",
+        HcErrorCode::DeponentNoFormForVoice => c"DeponentNoFormForVoice",
+        HcErrorCode::IllegalForm => c"IllegalForm",
+        HcErrorCode::DoesNotExist => c"DoesNotExist",
+        HcErrorCode::NoFormForUnit => c"NoFormForUnit",
+        HcErrorCode::NotImplemented => c"NotImplemented",
+        HcErrorCode::InvalidArgument => c"InvalidArgument",
+    };
+    name.as_ptr()
+}
@@ -0,0 +1,436 @@
+//! The state of a game of Hoplite Challenge, as a pure state machine which a server and its
+//! clients can share.
+//!
+//! Players take turns round the table.  On a turn one player changes `changes` of the
+//! current form's parameters (person, number, tense, voice, mood and, for participles,
+//! gender and case; the verb may change too, which isn't counted) and the next player still
+//! in the game must give the new form, within `answer_time` milliseconds if that isn't 0.
+//! A right answer scores a point; a wrong or late one costs a life, and a player with no
+//! lives left is out.  The player who answered changes the form for the next.  The game is
+//! over when one player is left.
+//!
+//! Nothing here reads a clock: every move carries its time, in milliseconds from any epoch
+//! the caller likes, and times may not go backwards.  A game is its config and the moves
+//! applied to it, so `HcGame::replay` rebuilds it from a move log; a whole game serializes
+//! as a snapshot, log included, and is checked against its replay when restored.  Answers
+//! are graded with `grading`, under the profile named in the config.
+
+use super::*;
+use grading::{grade_form, HcGradingProfile};
+use parameters::HcParameterSet;
+use serde::{Deserialize, Serialize};
+
+/// the longest `answer_time`, a day in milliseconds
+pub const MAX_ANSWER_TIME: u64 = 24 * 60 * 60 * 1000;
+
+/// a verb and a form of it, the form being exactly its parameters as `HcParameterSet` prints
+/// them, e.g. "3; sg; aor; act; ind"
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct HcGameForm {
+    pub verb: u32,
+    pub form: String,
+}
+
+impl HcGameForm {
+    pub fn from_form(vf: &HcGreekVerbForm) -> HcGameForm {
+        HcGameForm {
+            verb: vf.verb.id,
+            form: HcParameterSet::from(vf).to_string(),
+        }
+    }
+
+    pub fn to_form(&self, verbs: &[Arc<HcGreekVerb>]) -> Result<HcGreekVerbForm, HcGameError> {
+        let verb = verbs
+            .iter()
+            .find(|v| v.id == self.verb)
+            .ok_or(HcGameError::UnknownVerb(self.verb))?;
+        self.form
+            .parse::<HcParameterSet>()
+            .ok()
+            .and_then(|set| set.to_form(verb))
+            .ok_or_else(|| HcGameError::BadForm(self.form.clone()))
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct HcGameConfig {
+    /// in turn order
+    pub players: Vec<String>,
+    pub lives: u32,
+    /// how many parameters are changed each turn
+    pub changes: u32,
+    /// milliseconds to answer in, 0 for no limit, at most `MAX_ANSWER_TIME`
+    pub answer_time: u64,
+    /// a grading profile's name, see `HcGradingProfile::from_name`
+    pub profile: String,
+    /// the form the first player changes
+    pub start: HcGameForm,
+    pub started_at: u64,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "move")]
+pub enum HcMove {
+    /// the player whose turn it is gives the next form
+    Change {
+        player: usize,
+        at: u64,
+        to: HcGameForm,
+    },
+    Answer {
+        player: usize,
+        at: u64,
+        answer: String,
+    },
+    /// anyone may call time once the answer is due
+    Timeout { at: u64 },
+}
+
+impl HcMove {
+    pub fn at(&self) -> u64 {
+        match self {
+            HcMove::Change { at, .. } | HcMove::Answer { at, .. } | HcMove::Timeout { at } => *at,
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "phase")]
+pub enum HcGamePhase {
+    Changing {
+        player: usize,
+    },
+    Answering {
+        player: usize,
+        /// the form before the change, for `HcGame::prompt`'s highlighting
+        prev: HcGameForm,
+        /// 0 if there is no limit
+        deadline: u64,
+    },
+    Over {
+        /// None if no one is left
+        winner: Option<usize>,
+    },
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct HcPlayer {
+    pub name: String,
+    pub score: u32,
+    pub lives: u32,
+}
+
+/// what a move did
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum HcGameEvent {
+    Changed {
+        player: usize,
+    },
+    Answered {
+        player: usize,
+        correct: bool,
+        /// the answer came after the deadline, which makes it wrong
+        late: bool,
+        correct_answer: String,
+        lives: u32,
+    },
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum HcGameError {
+    /// the config can't start a game
+    Config(String),
+    UnknownVerb(u32),
+    BadForm(String),
+    /// the form doesn't exist for the verb
+    NoSuchForm(HcFormError),
+    WrongChanges {
+        expected: u32,
+        changed: u32,
+    },
+    NotYourTurn,
+    /// e.g. an answer while the form is being changed
+    WrongPhase,
+    GameOver,
+    /// a timeout before the deadline
+    TooEarly,
+    TimeWentBackwards,
+    /// a snapshot which doesn't parse, or whose state its log doesn't lead to
+    BadSnapshot(String),
+}
+
+impl fmt::Display for HcGameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HcGameError::Config(reason) => write!(f, "bad game config: {}", reason),
+            HcGameError::UnknownVerb(id) => write!(f, "no verb with id {}", id),
+            HcGameError::BadForm(form) => write!(f, "not a single form: {}", form),
+            HcGameError::NoSuchForm(e) => write!(f, "no such form: {}", e),
+            HcGameError::WrongChanges { expected, changed } => write!(
+                f,
+                "{} parameters must be changed, not {}",
+                expected, changed
+            ),
+            HcGameError::NotYourTurn => write!(f, "it is not the player's turn"),
+            HcGameError::WrongPhase => write!(f, "the move can't be made now"),
+            HcGameError::GameOver => write!(f, "the game is over"),
+            HcGameError::TooEarly => write!(f, "the answer isn't due yet"),
+            HcGameError::TimeWentBackwards => write!(f, "the move is earlier than the last"),
+            HcGameError::BadSnapshot(reason) => write!(f, "bad snapshot: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for HcGameError {}
+
+/// how many of person, number, tense, voice, mood, gender and case differ
+pub fn changed_parameters(a: &HcGreekVerbForm, b: &HcGreekVerbForm) -> u32 {
+    [
+        a.person != b.person,
+        a.number != b.number,
+        a.tense != b.tense,
+        a.voice != b.voice,
+        a.mood != b.mood,
+        a.gender != b.gender,
+        a.case != b.case,
+    ]
+    .iter()
+    .filter(|changed| **changed)
+    .count() as u32
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct HcGame {
+    pub config: HcGameConfig,
+    pub players: Vec<HcPlayer>,
+    /// the form to give, or the last one given
+    pub form: HcGameForm,
+    pub phase: HcGamePhase,
+    /// the time of the last move
+    pub now: u64,
+    /// every move applied, in order
+    pub log: Vec<HcMove>,
+}
+
+impl HcGame {
+    pub fn new(config: HcGameConfig, verbs: &[Arc<HcGreekVerb>]) -> Result<HcGame, HcGameError> {
+        if config.players.len() < 2 {
+            return Err(HcGameError::Config(String::from("two players are needed")));
+        }
+        if config.lives == 0 {
+            return Err(HcGameError::Config(String::from("players need a life")));
+        }
+        if !(1..=7).contains(&config.changes) {
+            return Err(HcGameError::Config(String::from(
+                "between 1 and 7 parameters may be changed",
+            )));
+        }
+        if config.answer_time > MAX_ANSWER_TIME {
+            return Err(HcGameError::Config(String::from(
+                "the answer time may be at most a day; 0 is no limit",
+            )));
+        }
+        if HcGradingProfile::from_name(&config.profile).is_none() {
+            return Err(HcGameError::Config(format!(
+                "unknown grading profile: {}",
+                config.profile
+            )));
+        }
+        config
+            .start
+            .to_form(verbs)?
+            .get_form(false)
+            .map_err(HcGameError::NoSuchForm)?;
+        Ok(HcGame {
+            players: config
+                .players
+                .iter()
+                .map(|name| HcPlayer {
+                    name: name.clone(),
+                    score: 0,
+                    lives: config.lives,
+                })
+                .collect(),
+            form: config.start.clone(),
+            phase: HcGamePhase::Changing { player: 0 },
+            now: config.started_at,
+            log: vec![],
+            config,
+        })
+    }
+
+    /// the game after each move of a log, stopping at the first which can't be made
+    pub fn replay(
+        config: HcGameConfig,
+        moves: &[HcMove],
+        verbs: &[Arc<HcGreekVerb>],
+    ) -> Result<HcGame, HcGameError> {
+        let mut game = HcGame::new(config, verbs)?;
+        for m in moves {
+            game.apply(m.clone(), verbs)?;
+        }
+        Ok(game)
+    }
+
+    pub fn is_over(&self) -> bool {
+        matches!(self.phase, HcGamePhase::Over { .. })
+    }
+
+    /// the form asked for, its changed parameters between `start` and `end`, while it is
+    /// being answered
+    pub fn prompt(&self, verbs: &[Arc<HcGreekVerb>], start: &str, end: &str) -> Option<String> {
+        match &self.phase {
+            HcGamePhase::Answering { prev, .. } => {
+                let vf = self.form.to_form(verbs).ok()?;
+                let prev = prev.to_form(verbs).ok()?;
+                Some(format!(
+                    "{}: {}",
+                    vf.verb.get_verb_lemma(),
+                    vf.get_description(&prev, start, end)
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// makes a move, or leaves the game as it was if it can't be made
+    pub fn apply(
+        &mut self,
+        m: HcMove,
+        verbs: &[Arc<HcGreekVerb>],
+    ) -> Result<HcGameEvent, HcGameError> {
+        if self.is_over() {
+            return Err(HcGameError::GameOver);
+        }
+        if m.at() < self.now {
+            return Err(HcGameError::TimeWentBackwards);
+        }
+        let event = match (&m, &self.phase) {
+            (HcMove::Change { player, at, to }, HcGamePhase::Changing { player: turn }) => {
+                if player != turn {
+                    return Err(HcGameError::NotYourTurn);
+                }
+                let vf = to.to_form(verbs)?;
+                vf.get_form(false).map_err(HcGameError::NoSuchForm)?;
+                let changed = changed_parameters(&self.form.to_form(verbs)?, &vf);
+                if changed != self.config.changes {
+                    return Err(HcGameError::WrongChanges {
+                        expected: self.config.changes,
+                        changed,
+                    });
+                }
+                self.phase = HcGamePhase::Answering {
+                    player: self.next_player(*player),
+                    prev: std::mem::replace(&mut self.form, to.clone()),
+                    deadline: match self.config.answer_time {
+                        0 => 0,
+                        time => at.saturating_add(time),
+                    },
+                };
+                HcGameEvent::Changed { player: *player }
+            }
+            (
+                HcMove::Answer { player, at, answer },
+                HcGamePhase::Answering {
+                    player: turn,
+                    deadline,
+                    ..
+                },
+            ) => {
+                if player != turn {
+                    return Err(HcGameError::NotYourTurn);
+                }
+                let late = *deadline != 0 && *at > *deadline;
+                self.answered(*player, Some(answer), late, verbs)?
+            }
+            (
+                HcMove::Timeout { at },
+                HcGamePhase::Answering {
+                    player, deadline, ..
+                },
+            ) => {
+                if *deadline == 0 || at < deadline {
+                    return Err(HcGameError::TooEarly);
+                }
+                self.answered(*player, None, true, verbs)?
+            }
+            _ => return Err(HcGameError::WrongPhase),
+        };
+        self.now = m.at();
+        self.log.push(m);
+        Ok(event)
+    }
+
+    fn answered(
+        &mut self,
+        player: usize,
+        answer: Option<&String>,
+        late: bool,
+        verbs: &[Arc<HcGreekVerb>],
+    ) -> Result<HcGameEvent, HcGameError> {
+        let profile = HcGradingProfile::from_name(&self.config.profile).unwrap_or_default();
+        let vf = self.form.to_form(verbs)?;
+        let grade = grade_form(&vf, answer.map_or("", |a| a.as_str()), &profile)
+            .map_err(HcGameError::NoSuchForm)?;
+        let correct = grade.correct && !late;
+        let p = &mut self.players[player];
+        if correct {
+            p.score += 1;
+        } else {
+            p.lives -= 1;
+        }
+        let lives = p.lives;
+
+        let left: Vec<usize> = (0..self.players.len())
+            .filter(|i| self.players[*i].lives > 0)
+            .collect();
+        self.phase = if left.len() < 2 {
+            HcGamePhase::Over {
+                winner: left.first().copied(),
+            }
+        } else if lives == 0 {
+            // out, so the next player changes the form
+            HcGamePhase::Changing {
+                player: self.next_player(player),
+            }
+        } else {
+            HcGamePhase::Changing { player }
+        };
+        Ok(HcGameEvent::Answered {
+            player,
+            correct,
+            late,
+            correct_answer: grade.correct_answer,
+            lives,
+        })
+    }
+
+    // the next player round the table with lives left
+    fn next_player(&self, player: usize) -> usize {
+        let count = self.players.len();
+        (1..=count)
+            .map(|i| (player + i) % count)
+            .find(|i| self.players[*i].lives > 0)
+            .unwrap_or(player)
+    }
+
+    pub fn snapshot(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// the game of a snapshot, which must be the one its config and log replay to
+    pub fn from_snapshot(
+        snapshot: &str,
+        verbs: &[Arc<HcGreekVerb>],
+    ) -> Result<HcGame, HcGameError> {
+        let game: HcGame =
+            serde_json::from_str(snapshot).map_err(|e| HcGameError::BadSnapshot(e.to_string()))?;
+        let replayed = HcGame::replay(game.config.clone(), &game.log, verbs)?;
+        if replayed != game {
+            return Err(HcGameError::BadSnapshot(String::from(
+                "the state isn't what the log replays to",
+            )));
+        }
+        Ok(game)
+    }
+}
@@ -0,0 +1,227 @@
+//! Grading a student's answer, a form or a verb's six principal parts, under a named profile
+//! of tolerances.
+//!
+//! Teachers differ on what they accept, so a profile says whether macrons and breves must be
+//! typed, whether a grave on the final syllable may stand for the acute, whether a movable ν
+//! must be written and whether every alternate must be given or one is enough.  Three
+//! profiles are built in:
+//!
+//! | profile    | vowel length | final grave | movable ν | alternates |
+//! |------------|--------------|-------------|-----------|------------|
+//! | `strict`   | required     | wrong       | required  | all        |
+//! | `standard` | ignored      | wrong       | optional  | all        |
+//! | `lenient`  | ignored      | accepted    | optional  | any one    |
+//!
+//! `standard` is the default.  An answer's alternates may be separated by `,`, `/` or `;`.
+//! Answers are first normalized by `input::normalize_input`, and the grade says which of its
+//! steps changed the answer.
+
+use super::*;
+use input::{normalize_input, HcNormalization, HcNormalizedInput};
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcGradingProfile {
+    /// recorded in each grade
+    pub name: String,
+    /// macrons and breves must be typed as the form has them
+    pub require_vowel_length: bool,
+    /// a grave on the final syllable (ἐπαίδευσὰ) is accepted for the acute
+    pub final_grave_for_acute: bool,
+    /// παιδεύουσι(ν) must be written παιδεύουσιν or παιδεύουσι(ν), not παιδεύουσι
+    pub require_movable_nu: bool,
+    /// ἔστησα / ἔστην needs both; otherwise either is enough
+    pub require_all_alternates: bool,
+}
+
+impl HcGradingProfile {
+    pub fn strict() -> HcGradingProfile {
+        HcGradingProfile {
+            name: String::from("strict"),
+            require_vowel_length: true,
+            final_grave_for_acute: false,
+            require_movable_nu: true,
+            require_all_alternates: true,
+        }
+    }
+
+    pub fn standard() -> HcGradingProfile {
+        HcGradingProfile {
+            name: String::from("standard"),
+            require_vowel_length: false,
+            final_grave_for_acute: false,
+            require_movable_nu: false,
+            require_all_alternates: true,
+        }
+    }
+
+    pub fn lenient() -> HcGradingProfile {
+        HcGradingProfile {
+            name: String::from("lenient"),
+            require_vowel_length: false,
+            final_grave_for_acute: true,
+            require_movable_nu: false,
+            require_all_alternates: false,
+        }
+    }
+
+    /// a built-in profile by name, ignoring case
+    pub fn from_name(name: &str) -> Option<HcGradingProfile> {
+        [Self::strict(), Self::standard(), Self::lenient()]
+            .into_iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl Default for HcGradingProfile {
+    fn default() -> HcGradingProfile {
+        HcGradingProfile::standard()
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcGrade {
+    pub correct: bool,
+    /// the form as get_form() gives it
+    pub correct_answer: String,
+    /// the name of the profile the answer was graded under
+    pub profile: String,
+    /// what was changed in the answer before it was graded
+    pub normalizations: Vec<HcNormalization>,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcPrincipalPartsGrade {
+    /// one for each principal part, all false if the answer doesn't have six
+    pub correct: Vec<bool>,
+    pub profile: String,
+    pub normalizations: Vec<HcNormalization>,
+    /// what was changed in each principal part given, each normalized on its own
+    pub part_normalizations: Vec<Vec<HcNormalization>>,
+}
+
+/// grades an answer for a form; the form's own errors (no such form, ...) are passed on
+pub fn grade_form(
+    vf: &HcGreekVerbForm,
+    answer: &str,
+    profile: &HcGradingProfile,
+) -> Result<HcGrade, HcFormError> {
+    let alternates = vf.get_form_alternates(false)?;
+    let input = normalize_input(answer);
+    Ok(HcGrade {
+        correct: matches(&alternates, &input.text, profile),
+        correct_answer: join_alternates(&alternates),
+        profile: profile.name.clone(),
+        normalizations: input.applied,
+    })
+}
+
+/// grades a comma separated list of six principal parts, each of which may have alternates
+/// separated by `/` or `;`
+pub fn grade_pps(
+    input: &str,
+    verb: &HcGreekVerb,
+    profile: &HcGradingProfile,
+) -> HcPrincipalPartsGrade {
+    let parts: Vec<HcNormalizedInput> = input.split(',').map(normalize_input).collect();
+    let pps = parts.iter().map(|p| p.text.as_str()).collect::<Vec<_>>();
+    let correct = if pps.len() != 6 || verb.pps.len() != 6 {
+        //todo
+        //if pps count is not 6, could check if there are 6 semi-colons or 6 slashes
+        //and split on that instead of immediately failing
+        vec![false; 6]
+    } else {
+        pps.iter()
+            .zip(&verb.pps)
+            .map(|(answer, pp)| {
+                let alternates: Vec<HcAlternate> = pp
+                    .split(['/', ';'])
+                    .enumerate()
+                    .map(|(i, a)| HcAlternate::new(a.trim().to_string(), i, 0))
+                    .collect();
+                matches(&alternates, answer, profile)
+            })
+            .collect()
+    };
+    HcPrincipalPartsGrade {
+        correct,
+        profile: profile.name.clone(),
+        normalizations: normalize_input(input).applied,
+        part_normalizations: parts.into_iter().map(|p| p.applied).collect(),
+    }
+}
+
+/// whether an answer, its alternates separated by `,`, `/` or `;`, matches the alternates
+/// of a form under the profile.  Every alternate given must be right.
+pub fn grade_answer(expected: &[HcAlternate], answer: &str, profile: &HcGradingProfile) -> bool {
+    matches(expected, &normalize_input(answer).text, profile)
+}
+
+// grade_answer, the answer already normalized
+fn matches(expected: &[HcAlternate], answer: &str, profile: &HcGradingProfile) -> bool {
+    let accepted: Vec<Vec<String>> = expected
+        .iter()
+        .map(|a| accepted_spellings(a, profile))
+        .collect();
+    let mut matched = vec![false; expected.len()];
+    let mut given = 0;
+    for word in answer
+        .split([',', '/', ';'])
+        .map(str::trim)
+        .filter(|w| !w.is_empty())
+    {
+        let word = normalize(word, profile);
+        let mut found = false;
+        // alternates which differ only in vowel length are matched together
+        for (i, spellings) in accepted.iter().enumerate() {
+            if spellings.contains(&word) {
+                matched[i] = true;
+                found = true;
+            }
+        }
+        if !found {
+            return false;
+        }
+        given += 1;
+    }
+    given > 0 && (!profile.require_all_alternates || matched.iter().all(|m| *m))
+}
+
+fn accepted_spellings(alternate: &HcAlternate, profile: &HcGradingProfile) -> Vec<String> {
+    let mut spellings = vec![alternate.form.clone()];
+    if alternate.has_movable_nu() {
+        spellings.push(alternate.form.replace("(ν)", "ν"));
+        if !profile.require_movable_nu {
+            spellings.push(alternate.form.replace("(ν)", ""));
+        }
+    }
+    spellings.iter().map(|s| normalize(s, profile)).collect()
+}
+
+// precomposed, without vowel length if the profile ignores it, and with a final grave
+// made acute if the profile accepts it
+fn normalize(word: &str, profile: &HcGradingProfile) -> String {
+    let mut letters: Vec<HGKLetter> = word.gkletters().collect();
+    if !profile.require_vowel_length {
+        for l in letters.iter_mut() {
+            l.toggle_diacritic(HGK_MACRON | HGK_BREVE, false);
+        }
+    }
+    if profile.final_grave_for_acute {
+        // the accent of the final syllable is on its last vowel
+        let last_vowel = letters.iter_mut().rev().find(|l| {
+            matches!(
+                l.letter_type(),
+                HgkLetterType::HgkLongVowel | HgkLetterType::HgkShortVowel
+            )
+        });
+        if let Some(l) = last_vowel {
+            if l.diacritics & HGK_GRAVE != 0 {
+                l.toggle_diacritic(HGK_ACUTE, true);
+            }
+        }
+    }
+    letters
+        .iter()
+        .map(|l| l.to_string(HgkUnicodeMode::Precomposed))
+        .collect()
+}
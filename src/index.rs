@@ -0,0 +1,215 @@
+//! An index from every form of a lexicon to the verbs and parameters which produce it,
+//! for looking up forms met in a text.
+//!
+//! Keys are normalized: lowercased, without macrons or breves (texts don't mark vowel
+//! length) and, if the index is built accent-insensitive, without accents or breathings.
+//! A form with a movable ν, e.g. παιδεύουσι(ν), is indexed both with and without the ν.
+//! Each entry notes which of a cell's alternates it came from, the first (0) being the
+//! main form.
+//!
+//! The on-disk format is little-endian:
+//!
+//! ```text
+//! "HCFI" version:u8 flags:u8 keys:u32
+//! then for each key: len:u16 utf8 entries:u16, each entry verb_id:u32 parameters:u32 alternate:u8
+//! ```
+
+use super::*;
+use parameters::HcParameterSet;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+static MAGIC: &[u8; 4] = b"HCFI";
+const VERSION: u8 = 1;
+const ACCENT_INSENSITIVE: u8 = 0x01;
+// the most keys or entries read() allocates room for before reading them
+const MAX_PREALLOCATION: u32 = 1 << 16;
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
+pub struct HcIndexEntry {
+    pub verb_id: u32,
+    /// exactly the form's parameters; `parameters.to_form(verb)` gives the form back
+    pub parameters: HcParameterSet,
+    /// 0 for the main form, 1 for the first alternate, ...
+    pub alternate: u8,
+}
+
+#[derive(Eq, PartialEq, Debug, Default)]
+pub struct HcFormIndex {
+    accent_insensitive: bool,
+    entries: HashMap<String, Vec<HcIndexEntry>>,
+}
+
+impl HcFormIndex {
+    /// generates every legal form (finite, infinitive and participle) of every verb; an
+    /// error if a cell has more alternates than an entry can number
+    pub fn build(verbs: &[Arc<HcGreekVerb>], accent_insensitive: bool) -> io::Result<HcFormIndex> {
+        let mut index = HcFormIndex {
+            accent_insensitive,
+            entries: HashMap::new(),
+        };
+        let all = HcParameterSet::new();
+        let forms: Vec<HcGreekVerbForm> = verbs.iter().flat_map(|v| all.forms(v)).collect();
+        for (vf, generated) in forms.iter().zip(generate_form_alternates(&forms, false)) {
+            let Ok(generated) = generated else {
+                continue;
+            };
+            for (alternate, form) in generated.iter().enumerate() {
+                index.insert(
+                    form,
+                    HcIndexEntry {
+                        verb_id: vf.verb.id,
+                        parameters: HcParameterSet::from(vf),
+                        alternate: u8::try_from(alternate)
+                            .map_err(|_| too_large("alternates of a form"))?,
+                    },
+                );
+            }
+        }
+        Ok(index)
+    }
+
+    fn insert(&mut self, form: &HcAlternate, entry: HcIndexEntry) {
+        for form in form.spellings() {
+            let entries = self
+                .entries
+                .entry(normalize_key(&form, self.accent_insensitive))
+                .or_default();
+            // alternates which only differ in what the key leaves out count once
+            if !entries
+                .iter()
+                .any(|e| e.verb_id == entry.verb_id && e.parameters == entry.parameters)
+            {
+                entries.push(entry);
+            }
+        }
+    }
+
+    /// the verbs and parameters which produce a form, in lexicon order
+    pub fn lookup(&self, form: &str) -> &[HcIndexEntry] {
+        self.entries
+            .get(&normalize_key(form, self.accent_insensitive))
+            .map_or(&[], |e| e.as_slice())
+    }
+
+    pub fn is_accent_insensitive(&self) -> bool {
+        self.accent_insensitive
+    }
+
+    /// the number of distinct keys
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// writes the keys in sorted order, so the same lexicon always gives the same file
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&[
+            VERSION,
+            if self.accent_insensitive {
+                ACCENT_INSENSITIVE
+            } else {
+                0
+            },
+        ])?;
+        let key_count = u32::try_from(self.entries.len()).map_err(|_| too_large("keys"))?;
+        w.write_all(&key_count.to_le_bytes())?;
+
+        let mut keys: Vec<&String> = self.entries.keys().collect();
+        keys.sort();
+        for key in keys {
+            let entries = &self.entries[key];
+            let len = u16::try_from(key.len()).map_err(|_| too_large("bytes in a key"))?;
+            w.write_all(&len.to_le_bytes())?;
+            w.write_all(key.as_bytes())?;
+            let count = u16::try_from(entries.len()).map_err(|_| too_large("entries for a key"))?;
+            w.write_all(&count.to_le_bytes())?;
+            for e in entries {
+                w.write_all(&e.verb_id.to_le_bytes())?;
+                w.write_all(&e.parameters.bits().to_le_bytes())?;
+                w.write_all(&[e.alternate])?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn read<R: Read>(r: &mut R) -> io::Result<HcFormIndex> {
+        let magic: [u8; 4] = read_array(r)?;
+        let [version, flags] = read_array(r)?;
+        if &magic != MAGIC || version != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a form index file",
+            ));
+        }
+
+        let key_count = u32::from_le_bytes(read_array(r)?);
+        // the counts aren't trusted until the data is there: a corrupt one would ask for
+        // gigabytes
+        let mut entries = HashMap::with_capacity(key_count.min(MAX_PREALLOCATION) as usize);
+        for _ in 0..key_count {
+            let len = u16::from_le_bytes(read_array(r)?);
+            let mut key = vec![0; len as usize];
+            read_bytes(r, &mut key)?;
+            let key = String::from_utf8(key)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+            let count = u16::from_le_bytes(read_array(r)?);
+            let mut key_entries =
+                Vec::with_capacity(u32::from(count).min(MAX_PREALLOCATION) as usize);
+            for _ in 0..count {
+                let verb_id = u32::from_le_bytes(read_array(r)?);
+                let bits = u32::from_le_bytes(read_array(r)?);
+                let [alternate] = read_array(r)?;
+                key_entries.push(HcIndexEntry {
+                    verb_id,
+                    parameters: HcParameterSet::from_bits(bits),
+                    alternate,
+                });
+            }
+            entries.insert(key, key_entries);
+        }
+        Ok(HcFormIndex {
+            accent_insensitive: flags & ACCENT_INSENSITIVE != 0,
+            entries,
+        })
+    }
+}
+
+// more of something than the on-disk format has room for
+fn too_large(what: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("too many {} for a form index", what),
+    )
+}
+
+// a file which ends early is as bad as one with the wrong data
+fn read_bytes<R: Read>(r: &mut R, buf: &mut [u8]) -> io::Result<()> {
+    r.read_exact(buf).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => {
+            io::Error::new(io::ErrorKind::InvalidData, "the form index is truncated")
+        }
+        _ => e,
+    })
+}
+
+fn read_array<R: Read, const N: usize>(r: &mut R) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    read_bytes(r, &mut buf)?;
+    Ok(buf)
+}
+
+/// the key a form is stored under
+pub fn normalize_key(form: &str, accent_insensitive: bool) -> String {
+    let diacritics = if accent_insensitive {
+        HGK_MACRON | HGK_BREVE | HGK_ACUTE | HGK_GRAVE | HGK_CIRCUMFLEX | HGK_SMOOTH | HGK_ROUGH
+    } else {
+        HGK_MACRON | HGK_BREVE
+    };
+    hgk_strip_diacritics(form.trim(), diacritics).to_lowercase()
+}
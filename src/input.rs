@@ -0,0 +1,272 @@
+//! Normalizing Greek as students type it, before it is graded.
+//!
+//! Keyboards and habits differ, so an answer goes through these steps, in order:
+//!
+//! 1. `Blank`: `--`, `---` and the en and horizontal bars (– ―) become the em dash (—)
+//!    of a blank principal part.
+//! 2. `LatinLetter`: in a word with a Greek letter, Latin letters which look Greek
+//!    (o, v, A, ...) become the Greek ones.
+//! 3. `LunateSigma`: ϲ and Ϲ become σ and Σ.
+//! 4. `FinalSigma`: σ at the end of a word becomes ς, and ς within one σ.
+//! 5. `Apostrophe`: the marks of elision (' ʼ ‘ ᾽ ᾿) become ’.
+//! 6. `Oxia`: the acute's oxia code points (ά U+1F71) become tonos (ά U+03AC), as NFC has
+//!    them.
+//! 7. `CombiningMarks`: a letter typed with combining marks, in any order, is precomposed.
+//!
+//! Separators (`,`, `/`, `;`) are left alone.  Each step is reported once if it changed
+//! anything, so a front end can nudge a student toward typing the answer as it is printed.
+
+use super::*;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, Ord, PartialOrd)]
+pub enum HcNormalization {
+    Blank,
+    LatinLetter,
+    LunateSigma,
+    FinalSigma,
+    Apostrophe,
+    Oxia,
+    CombiningMarks,
+}
+
+impl HcNormalization {
+    pub fn value(&self) -> &str {
+        match self {
+            HcNormalization::Blank => "Blank",
+            HcNormalization::LatinLetter => "LatinLetter",
+            HcNormalization::LunateSigma => "LunateSigma",
+            HcNormalization::FinalSigma => "FinalSigma",
+            HcNormalization::Apostrophe => "Apostrophe",
+            HcNormalization::Oxia => "Oxia",
+            HcNormalization::CombiningMarks => "CombiningMarks",
+        }
+    }
+}
+
+impl fmt::Display for HcNormalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HcNormalization::Blank => "a blank principal part is written —",
+            HcNormalization::LatinLetter => "a Latin letter was typed for a Greek one",
+            HcNormalization::LunateSigma => "the lunate sigma (ϲ) was typed for σ or ς",
+            HcNormalization::FinalSigma => "σ and ς were confused",
+            HcNormalization::Apostrophe => "elision is marked with ’",
+            HcNormalization::Oxia => "an acute was typed with an oxia code point",
+            HcNormalization::CombiningMarks => "diacritics were typed as combining marks",
+        })
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcNormalizedInput {
+    pub text: String,
+    /// the steps which changed the input, in the order they are applied
+    pub applied: Vec<HcNormalization>,
+}
+
+const BLANK: char = '—';
+const APOSTROPHE: char = '\u{2019}';
+const APOSTROPHES: [char; 5] = ['\'', '\u{02BC}', '\u{2018}', '\u{1FBD}', '\u{1FBF}'];
+const OXIA: [(char, char); 16] = [
+    ('\u{1F71}', '\u{03AC}'),
+    ('\u{1F73}', '\u{03AD}'),
+    ('\u{1F75}', '\u{03AE}'),
+    ('\u{1F77}', '\u{03AF}'),
+    ('\u{1F79}', '\u{03CC}'),
+    ('\u{1F7B}', '\u{03CD}'),
+    ('\u{1F7D}', '\u{03CE}'),
+    ('\u{1FBB}', '\u{0386}'),
+    ('\u{1FC9}', '\u{0388}'),
+    ('\u{1FCB}', '\u{0389}'),
+    ('\u{1FDB}', '\u{038A}'),
+    ('\u{1FF9}', '\u{038C}'),
+    ('\u{1FEB}', '\u{038E}'),
+    ('\u{1FFB}', '\u{038F}'),
+    ('\u{1FD3}', '\u{0390}'),
+    ('\u{1FE3}', '\u{03B0}'),
+];
+const LATIN_LETTERS: [(char, char); 22] = [
+    ('a', 'α'),
+    ('i', 'ι'),
+    ('k', 'κ'),
+    ('o', 'ο'),
+    ('p', 'ρ'),
+    ('u', 'υ'),
+    ('v', 'ν'),
+    ('x', 'χ'),
+    ('A', 'Α'),
+    ('B', 'Β'),
+    ('E', 'Ε'),
+    ('H', 'Η'),
+    ('I', 'Ι'),
+    ('K', 'Κ'),
+    ('M', 'Μ'),
+    ('N', 'Ν'),
+    ('O', 'Ο'),
+    ('P', 'Ρ'),
+    ('T', 'Τ'),
+    ('X', 'Χ'),
+    ('Y', 'Υ'),
+    ('Z', 'Ζ'),
+];
+
+/// normalizes an answer as described in the module's documentation
+pub fn normalize_input(input: &str) -> HcNormalizedInput {
+    let mut applied = vec![];
+    let mut text = blank(input);
+    if text != input {
+        applied.push(HcNormalization::Blank);
+    }
+    for (step, f) in [
+        (
+            HcNormalization::LatinLetter,
+            latin_letters as fn(&str) -> String,
+        ),
+        (HcNormalization::LunateSigma, lunate_sigma),
+        (HcNormalization::FinalSigma, final_sigma),
+        (HcNormalization::Apostrophe, apostrophes),
+        (HcNormalization::Oxia, oxia),
+        (HcNormalization::CombiningMarks, precompose),
+    ] {
+        let normalized = f(&text);
+        if normalized != text {
+            applied.push(step);
+            text = normalized;
+        }
+    }
+    HcNormalizedInput { text, applied }
+}
+
+fn is_greek(c: char) -> bool {
+    matches!(c, '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}')
+}
+
+fn is_combining(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}')
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphabetic() || is_combining(c)
+}
+
+// the words of the text, with what is between them
+fn map_words(text: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut out = String::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if is_word_char(c) {
+            word.push(c);
+        } else {
+            if !word.is_empty() {
+                out.push_str(&f(&word));
+                word.clear();
+            }
+            out.push(c);
+        }
+    }
+    if !word.is_empty() {
+        out.push_str(&f(&word));
+    }
+    out
+}
+
+fn blank(text: &str) -> String {
+    let mut out = String::new();
+    let mut hyphens = 0;
+    for c in text.chars().chain(['\0']) {
+        if c == '-' {
+            hyphens += 1;
+            continue;
+        }
+        match hyphens {
+            0 => (),
+            1 => out.push('-'),
+            _ => out.push(BLANK),
+        }
+        hyphens = 0;
+        match c {
+            '\0' => (),
+            '\u{2013}' | '\u{2015}' => out.push(BLANK),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn latin_letters(text: &str) -> String {
+    map_words(text, |word| {
+        if !word.chars().any(is_greek) {
+            return word.to_string();
+        }
+        word.chars()
+            .map(|c| {
+                LATIN_LETTERS
+                    .iter()
+                    .find(|(latin, _)| *latin == c)
+                    .map_or(c, |(_, greek)| *greek)
+            })
+            .collect()
+    })
+}
+
+fn lunate_sigma(text: &str) -> String {
+    text.replace('\u{03F2}', "σ").replace('\u{03F9}', "Σ")
+}
+
+fn final_sigma(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            if c != 'σ' && c != 'ς' {
+                return c;
+            }
+            // not counting combining marks; an elided word (ἔλυσ’) keeps its σ
+            let next = chars[i + 1..].iter().find(|c| !is_combining(**c));
+            let ends_word = next.is_none_or(|n| !is_word_char(*n) && !is_apostrophe(*n));
+            let in_word = i > 0 && is_word_char(chars[i - 1]);
+            match c {
+                'σ' if ends_word && in_word => 'ς',
+                'ς' if !ends_word => 'σ',
+                c => c,
+            }
+        })
+        .collect()
+}
+
+fn is_apostrophe(c: char) -> bool {
+    c == APOSTROPHE || APOSTROPHES.contains(&c)
+}
+
+fn apostrophes(text: &str) -> String {
+    text.replace(APOSTROPHES, &APOSTROPHE.to_string())
+}
+
+/// the acute's oxia code points as tonos, which generated forms use
+pub fn oxia(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            OXIA.iter()
+                .find(|(oxia, _)| *oxia == c)
+                .map_or(c, |(_, tonos)| *tonos)
+        })
+        .collect()
+}
+
+fn precompose(text: &str) -> String {
+    if !text.chars().any(is_combining) {
+        return text.to_string();
+    }
+    map_words(text, |word| {
+        if !word.chars().any(is_combining) {
+            return word.to_string();
+        }
+        oxia(
+            &word
+                .gkletters()
+                .map(|l| l.to_string(HgkUnicodeMode::Precomposed))
+                .collect::<String>(),
+        )
+    })
+}
@@ -0,0 +1,221 @@
+//! A learner model: how well a student knows each area of the verb, from their graded
+//! answers, for steering drills toward their weak areas.
+//!
+//! A form exercises several skills at once.  ἐπαιδεύθην, for instance, exercises the
+//! aorist, the passive, the aorist passive and the indicative.  Some skills are rules of
+//! the verb rather than its parameters: contraction, the consonant-stem perfect middle
+//! (verbs with a `CONSONANT_STEM_PERFECT_*` flag) and each kind of deponent
+//! (`deponent_type()`).  Only a skill's last `window` answers are counted, so that mastery
+//! follows the student as they improve.  Mastery is (right + 1) / (answers + 2), so a skill
+//! not yet practised is at one half.
+//!
+//! `choose` picks a form from candidates at random, weighting each by the square of its
+//! weakest skill's shortfall (1 − mastery), and says why:
+//! "you have missed 4/5 perfect middle consonant-stem forms".
+
+use super::*;
+use grading::HcGrade;
+use std::collections::{HashMap, VecDeque};
+
+const CONSONANT_STEM_PERFECT: u32 = CONSONANT_STEM_PERFECT_PHI
+    | CONSONANT_STEM_PERFECT_MU_PI
+    | CONSONANT_STEM_PERFECT_KAPPA
+    | CONSONANT_STEM_PERFECT_SIGMA
+    | CONSONANT_STEM_PERFECT_SIGMA_2
+    | CONSONANT_STEM_PERFECT_LAMBDA
+    | CONSONANT_STEM_PERFECT_PI
+    | CONSONANT_STEM_PERFECT_BETA
+    | CONSONANT_STEM_PERFECT_GAMMA
+    | CONSONANT_STEM_PERFECT_CHI
+    | CONSONANT_STEM_PERFECT_NU;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+pub enum HcSkill {
+    ConsonantStemPerfectMiddle,
+    ContractVerb,
+    Deponent(HcDeponentType),
+    TenseVoice(HcTense, HcVoice),
+    Tense(HcTense),
+    Voice(HcVoice),
+    Mood(HcMood),
+}
+
+impl HcSkill {
+    /// e.g. "aorist passive forms"
+    pub fn description(&self) -> String {
+        let kind = match self {
+            HcSkill::ConsonantStemPerfectMiddle => String::from("perfect middle consonant-stem"),
+            HcSkill::ContractVerb => String::from("contract verb"),
+            HcSkill::Deponent(d) => d.value().to_lowercase(),
+            HcSkill::TenseVoice(t, v) => format!("{} {}", t.value(), v.value()).to_lowercase(),
+            HcSkill::Tense(t) => t.value().to_lowercase(),
+            HcSkill::Voice(v) => v.value().to_lowercase(),
+            HcSkill::Mood(m) => m.value().to_lowercase(),
+        };
+        format!("{} forms", kind)
+    }
+}
+
+/// the skills a form exercises, the rules of the verb first
+pub fn skills(vf: &HcGreekVerbForm) -> Vec<HcSkill> {
+    let mut skills = vec![];
+    if vf.verb.properties & CONSONANT_STEM_PERFECT != 0
+        && matches!(vf.tense, HcTense::Perfect | HcTense::Pluperfect)
+        && vf.voice != HcVoice::Active
+    {
+        skills.push(HcSkill::ConsonantStemPerfectMiddle);
+    }
+    if vf.get_pp().is_some_and(|pp| vf.is_contracted_verb(&pp)) {
+        skills.push(HcSkill::ContractVerb);
+    }
+    let deponent = vf.verb.deponent_type();
+    if deponent != HcDeponentType::NotDeponent {
+        skills.push(HcSkill::Deponent(deponent));
+    }
+    skills.extend([
+        HcSkill::TenseVoice(vf.tense, vf.voice),
+        HcSkill::Tense(vf.tense),
+        HcSkill::Voice(vf.voice),
+        HcSkill::Mood(vf.mood),
+    ]);
+    skills
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Default)]
+pub struct HcSkillStats {
+    /// within the window
+    pub answers: u32,
+    pub misses: u32,
+}
+
+impl HcSkillStats {
+    pub fn mastery(&self) -> f64 {
+        f64::from(self.answers - self.misses + 1) / f64::from(self.answers + 2)
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcChoice {
+    pub form: HcGreekVerbForm,
+    /// the weakest of the form's skills, for which it was chosen
+    pub skill: HcSkill,
+    pub explanation: String,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcLearnerModel {
+    /// how many of a skill's latest answers count
+    pub window: usize,
+    // latest last, true if right
+    history: HashMap<HcSkill, VecDeque<bool>>,
+}
+
+impl Default for HcLearnerModel {
+    fn default() -> HcLearnerModel {
+        HcLearnerModel::new(10)
+    }
+}
+
+impl HcLearnerModel {
+    pub fn new(window: usize) -> HcLearnerModel {
+        HcLearnerModel {
+            window,
+            history: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, vf: &HcGreekVerbForm, grade: &HcGrade) {
+        for skill in skills(vf) {
+            let answers = self.history.entry(skill).or_default();
+            answers.push_back(grade.correct);
+            while answers.len() > self.window {
+                answers.pop_front();
+            }
+        }
+    }
+
+    pub fn stats(&self, skill: &HcSkill) -> HcSkillStats {
+        self.history
+            .get(skill)
+            .map(|answers| HcSkillStats {
+                answers: answers.len() as u32,
+                misses: answers.iter().filter(|right| !**right).count() as u32,
+            })
+            .unwrap_or_default()
+    }
+
+    /// the skills practised, the least mastered first
+    pub fn weakest(&self) -> Vec<(HcSkill, HcSkillStats)> {
+        let mut skills: Vec<(HcSkill, HcSkillStats)> = self
+            .history
+            .keys()
+            .map(|skill| (*skill, self.stats(skill)))
+            .collect();
+        // then by description, so that the order doesn't depend on the map's
+        skills.sort_by(|a, b| {
+            a.1.mastery()
+                .total_cmp(&b.1.mastery())
+                .then_with(|| a.0.description().cmp(&b.0.description()))
+        });
+        skills
+    }
+
+    /// a form from the candidates, biased toward the student's weak areas; the same seed
+    /// gives the same choice
+    pub fn choose(&self, candidates: &[HcGreekVerbForm], seed: u64) -> Option<HcChoice> {
+        let weakest: Vec<(HcSkill, f64)> = candidates
+            .iter()
+            .map(|vf| {
+                // the first of the weakest, the rules coming first
+                skills(vf)
+                    .into_iter()
+                    .map(|skill| (skill, 1.0 - self.stats(&skill).mastery()))
+                    .fold(
+                        None,
+                        |weakest: Option<(HcSkill, f64)>, (skill, shortfall)| match weakest {
+                            Some((_, w)) if w >= shortfall => weakest,
+                            _ => Some((skill, shortfall)),
+                        },
+                    )
+                    .unwrap()
+            })
+            .collect();
+        let total: f64 = weakest.iter().map(|(_, w)| w * w).sum();
+        let mut target = SplitMix64(seed).next_f64() * total;
+        let i = weakest
+            .iter()
+            .position(|(_, w)| {
+                target -= w * w;
+                target < 0.0
+            })
+            .unwrap_or(candidates.len().checked_sub(1)?);
+        let skill = weakest[i].0;
+        Some(HcChoice {
+            form: candidates[i].clone(),
+            skill,
+            explanation: self.explain(&skill),
+        })
+    }
+
+    /// why a skill is drilled, e.g. "you have missed 4/5 aorist passive forms"
+    pub fn explain(&self, skill: &HcSkill) -> String {
+        let stats = self.stats(skill);
+        if stats.answers == 0 {
+            format!("you haven't practised {} yet", skill.description())
+        } else if stats.misses > 0 {
+            format!(
+                "you have missed {}/{} {}",
+                stats.misses,
+                stats.answers,
+                skill.description()
+            )
+        } else {
+            format!(
+                "you have answered {}/{} {} correctly",
+                stats.answers,
+                stats.answers,
+                skill.description()
+            )
+        }
+    }
+}
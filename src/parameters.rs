@@ -0,0 +1,510 @@
+//! A set of verb parameters over every axis, e.g. for choosing which forms to drill.
+//!
+//! Each axis (person, number, tense, voice, mood, gender, case) is a set of values kept in
+//! one bit each; an axis with nothing in it is unrestricted.  Sets are written as
+//! comma- or semicolon-separated abbreviations, the axis being known from the value:
+//!
+//! ```text
+//! aor,fut; mid,pass; opt
+//! 3; sg,pl; pres; act; ptc; masc; nom,acc
+//! ```
+//!
+//! and are printed back grouped by axis in the order above, e.g. `fut,aor; mid,pass; opt`.
+//!
+//! Sets combine with `|`, `&` and `-` as sets of forms, an unrestricted axis counting as
+//! every value but the dual, which is only included when asked for; `&` and `-` give
+//! `None` when no form is left.
+
+use super::*;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, Sub};
+use std::str::FromStr;
+
+static PERSONS: [HcPerson; 3] = [HcPerson::First, HcPerson::Second, HcPerson::Third];
+static NUMBERS: [HcNumber; 3] = [HcNumber::Singular, HcNumber::Dual, HcNumber::Plural];
+static TENSES: [HcTense; 6] = [
+    HcTense::Present,
+    HcTense::Imperfect,
+    HcTense::Future,
+    HcTense::Aorist,
+    HcTense::Perfect,
+    HcTense::Pluperfect,
+];
+static VOICES: [HcVoice; 3] = [HcVoice::Active, HcVoice::Middle, HcVoice::Passive];
+static MOODS: [HcMood; 6] = [
+    HcMood::Indicative,
+    HcMood::Subjunctive,
+    HcMood::Optative,
+    HcMood::Imperative,
+    HcMood::Infinitive,
+    HcMood::Participle,
+];
+static GENDERS: [HcGender; 3] = [HcGender::Masculine, HcGender::Feminine, HcGender::Neuter];
+static CASES: [HcCase; 5] = [
+    HcCase::Nominative,
+    HcCase::Genitive,
+    HcCase::Dative,
+    HcCase::Accusative,
+    HcCase::Vocative,
+];
+
+// abbreviations in the order of the bits, one table per axis
+static PERSON_NAMES: [&str; 3] = ["1", "2", "3"];
+static NUMBER_NAMES: [&str; 3] = ["sg", "du", "pl"];
+static TENSE_NAMES: [&str; 6] = ["pres", "impf", "fut", "aor", "perf", "plup"];
+static VOICE_NAMES: [&str; 3] = ["act", "mid", "pass"];
+static MOOD_NAMES: [&str; 6] = ["ind", "subj", "opt", "imper", "inf", "ptc"];
+static GENDER_NAMES: [&str; 3] = ["masc", "fem", "neut"];
+static CASE_NAMES: [&str; 5] = ["nom", "gen", "dat", "acc", "voc"];
+
+// (first bit, abbreviations) of each axis
+const PERSON_AXIS: (u32, &[&str]) = (0, &PERSON_NAMES);
+const NUMBER_AXIS: (u32, &[&str]) = (3, &NUMBER_NAMES);
+const TENSE_AXIS: (u32, &[&str]) = (6, &TENSE_NAMES);
+const VOICE_AXIS: (u32, &[&str]) = (12, &VOICE_NAMES);
+const MOOD_AXIS: (u32, &[&str]) = (15, &MOOD_NAMES);
+const GENDER_AXIS: (u32, &[&str]) = (21, &GENDER_NAMES);
+const CASE_AXIS: (u32, &[&str]) = (24, &CASE_NAMES);
+static AXES: [(u32, &[&str]); 7] = [
+    PERSON_AXIS,
+    NUMBER_AXIS,
+    TENSE_AXIS,
+    VOICE_AXIS,
+    MOOD_AXIS,
+    GENDER_AXIS,
+    CASE_AXIS,
+];
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash, Default)]
+pub struct HcParameterSet {
+    bits: u32,
+}
+
+impl HcParameterSet {
+    /// the empty set, i.e. every form
+    pub fn new() -> HcParameterSet {
+        HcParameterSet::default()
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    pub fn from_bits(bits: u32) -> HcParameterSet {
+        let all = AXES.iter().fold(0, |acc, (first, names)| {
+            acc | axis_mask(*first, names.len())
+        });
+        HcParameterSet { bits: bits & all }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn with_person(self, person: HcPerson) -> HcParameterSet {
+        self.with(PERSON_AXIS.0, position(&PERSONS, person))
+    }
+
+    pub fn with_number(self, number: HcNumber) -> HcParameterSet {
+        self.with(NUMBER_AXIS.0, position(&NUMBERS, number))
+    }
+
+    pub fn with_tense(self, tense: HcTense) -> HcParameterSet {
+        self.with(TENSE_AXIS.0, position(&TENSES, tense))
+    }
+
+    pub fn with_voice(self, voice: HcVoice) -> HcParameterSet {
+        self.with(VOICE_AXIS.0, position(&VOICES, voice))
+    }
+
+    pub fn with_mood(self, mood: HcMood) -> HcParameterSet {
+        self.with(MOOD_AXIS.0, position(&MOODS, mood))
+    }
+
+    pub fn with_gender(self, gender: HcGender) -> HcParameterSet {
+        self.with(GENDER_AXIS.0, position(&GENDERS, gender))
+    }
+
+    pub fn with_case(self, case: HcCase) -> HcParameterSet {
+        self.with(CASE_AXIS.0, position(&CASES, case))
+    }
+
+    fn with(self, first: u32, index: usize) -> HcParameterSet {
+        HcParameterSet {
+            bits: self.bits | 1 << (first + index as u32),
+        }
+    }
+
+    // the values allowed on an axis: the ones in the set, or those of an unrestricted axis
+    // if there are none
+    fn values<T: Copy>(&self, (first, names): (u32, &[&str]), values: &[T]) -> Vec<T> {
+        let mask = axis_mask(first, names.len());
+        let axis = allowed_bits(mask, self.bits & mask);
+        values
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| axis & (1 << (first + *i as u32)) != 0)
+            .map(|(_, v)| *v)
+            .collect()
+    }
+
+    fn allows<T: Copy + PartialEq>(
+        &self,
+        axis: (u32, &[&str]),
+        values: &[T],
+        value: Option<T>,
+    ) -> bool {
+        // an axis which doesn't apply to the form (e.g. an infinitive's person) is ignored
+        value.is_none_or(|v| self.values(axis, values).contains(&v))
+    }
+
+    pub fn persons(&self) -> Vec<HcPerson> {
+        self.values(PERSON_AXIS, &PERSONS)
+    }
+
+    /// the dual is only included when asked for, as H&Q doesn't teach it
+    pub fn numbers(&self) -> Vec<HcNumber> {
+        self.values(NUMBER_AXIS, &NUMBERS)
+    }
+
+    pub fn tenses(&self) -> Vec<HcTense> {
+        self.values(TENSE_AXIS, &TENSES)
+    }
+
+    pub fn voices(&self) -> Vec<HcVoice> {
+        self.values(VOICE_AXIS, &VOICES)
+    }
+
+    pub fn moods(&self) -> Vec<HcMood> {
+        self.values(MOOD_AXIS, &MOODS)
+    }
+
+    pub fn genders(&self) -> Vec<HcGender> {
+        self.values(GENDER_AXIS, &GENDERS)
+    }
+
+    pub fn cases(&self) -> Vec<HcCase> {
+        self.values(CASE_AXIS, &CASES)
+    }
+
+    /// the form of a set made from one form's parameters, see `From<&HcGreekVerbForm>`;
+    /// None if the set has more than one value on an axis or no tense, voice or mood
+    pub fn to_form(&self, verb: &Arc<HcGreekVerb>) -> Option<HcGreekVerbForm> {
+        fn single<T: Copy>(
+            set: &HcParameterSet,
+            axis: (u32, &[&str]),
+            values: &[T],
+        ) -> Option<Option<T>> {
+            let mask = axis_mask(axis.0, axis.1.len());
+            match (set.bits & mask).count_ones() {
+                0 => Some(None),
+                1 => Some(set.values(axis, values).first().copied()),
+                _ => None,
+            }
+        }
+        Some(HcGreekVerbForm {
+            verb: verb.clone(),
+            person: single(self, PERSON_AXIS, &PERSONS)?,
+            number: single(self, NUMBER_AXIS, &NUMBERS)?,
+            tense: single(self, TENSE_AXIS, &TENSES)??,
+            voice: single(self, VOICE_AXIS, &VOICES)??,
+            mood: single(self, MOOD_AXIS, &MOODS)??,
+            gender: single(self, GENDER_AXIS, &GENDERS)?,
+            case: single(self, CASE_AXIS, &CASES)?,
+        })
+    }
+
+    /// whether the set includes a form's parameters
+    pub fn matches(&self, vf: &HcGreekVerbForm) -> bool {
+        self.allows(PERSON_AXIS, &PERSONS, vf.person)
+            && self.allows(NUMBER_AXIS, &NUMBERS, vf.number)
+            && self.allows(TENSE_AXIS, &TENSES, Some(vf.tense))
+            && self.allows(VOICE_AXIS, &VOICES, Some(vf.voice))
+            && self.allows(MOOD_AXIS, &MOODS, Some(vf.mood))
+            && self.allows(GENDER_AXIS, &GENDERS, vf.gender)
+            && self.allows(CASE_AXIS, &CASES, vf.case)
+    }
+
+    /// every legal form of a verb in the set, by tense, voice and mood, then number and
+    /// person (finite moods) or number, gender and case (participles)
+    pub fn forms(&self, verb: &Arc<HcGreekVerb>) -> impl Iterator<Item = HcGreekVerbForm> {
+        let mut forms = Vec::new();
+        let form = |tense, voice, mood, person, number, gender, case| HcGreekVerbForm {
+            verb: verb.clone(),
+            person,
+            number,
+            tense,
+            voice,
+            mood,
+            gender,
+            case,
+        };
+        for tense in self.tenses() {
+            for voice in self.voices() {
+                for mood in self.moods() {
+                    match mood {
+                        HcMood::Infinitive => {
+                            forms.push(form(tense, voice, mood, None, None, None, None));
+                        }
+                        HcMood::Participle => {
+                            for number in self.numbers() {
+                                for gender in self.genders() {
+                                    for case in self.cases() {
+                                        forms.push(form(
+                                            tense,
+                                            voice,
+                                            mood,
+                                            None,
+                                            Some(number),
+                                            Some(gender),
+                                            Some(case),
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+                        _ => {
+                            for number in self.numbers() {
+                                for person in self.persons() {
+                                    forms.push(form(
+                                        tense,
+                                        voice,
+                                        mood,
+                                        Some(person),
+                                        Some(number),
+                                        None,
+                                        None,
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let set = *self;
+        forms.into_iter().filter(move |vf| {
+            vf.is_legal_form()
+                && !(matches!(vf.mood, HcMood::Infinitive | HcMood::Participle)
+                    && matches!(vf.tense, HcTense::Imperfect | HcTense::Pluperfect))
+                && set.matches(vf)
+        })
+    }
+}
+
+fn axis_mask(first: u32, len: usize) -> u32 {
+    ((1 << len) - 1) << first
+}
+
+fn position<T: PartialEq>(values: &[T], value: T) -> usize {
+    values.iter().position(|v| *v == value).unwrap()
+}
+
+// the values an unrestricted axis stands for: all of them but the dual
+fn unrestricted_bits(mask: u32) -> u32 {
+    let number_mask = axis_mask(NUMBER_AXIS.0, NUMBERS.len());
+    if mask == number_mask {
+        mask & !(1 << (NUMBER_AXIS.0 + position(&NUMBERS, HcNumber::Dual) as u32))
+    } else {
+        mask
+    }
+}
+
+fn allowed_bits(mask: u32, axis: u32) -> u32 {
+    if axis == 0 {
+        unrestricted_bits(mask)
+    } else {
+        axis
+    }
+}
+
+// each axis's mask, allowed bits and whether it is unrestricted
+fn axis_bits(bits: u32) -> impl Iterator<Item = (u32, u32, bool)> {
+    AXES.iter().map(move |(first, names)| {
+        let mask = axis_mask(*first, names.len());
+        (mask, allowed_bits(mask, bits & mask), bits & mask == 0)
+    })
+}
+
+// the set from each axis's bits; an axis is written as unrestricted again only if it was
+// in one of the sets it came from and still stands for the same values, so that a dual
+// asked for isn't lost
+fn from_axes(axes: impl Iterator<Item = (u32, u32, bool)>) -> HcParameterSet {
+    HcParameterSet {
+        bits: axes
+            .filter(|(mask, axis, unrestricted)| {
+                !(*unrestricted && *axis == unrestricted_bits(*mask))
+            })
+            .fold(0, |acc, (_, axis, _)| acc | axis),
+    }
+}
+
+impl HcParameterSet {
+    /// The smallest set holding the forms of both, e.g. `aor` and `mid; aor` give `aor`:
+    /// a union of two sets isn't always a set of this kind.
+    pub fn union(&self, other: &HcParameterSet) -> HcParameterSet {
+        from_axes(
+            axis_bits(self.bits)
+                .zip(axis_bits(other.bits))
+                .map(|((mask, a, ua), (_, b, ub))| (mask, a | b, ua || ub)),
+        )
+    }
+
+    /// The forms in both, or `None` if there are none.
+    pub fn intersection(&self, other: &HcParameterSet) -> Option<HcParameterSet> {
+        let axes: Vec<(u32, u32, bool)> = axis_bits(self.bits)
+            .zip(axis_bits(other.bits))
+            .map(|((mask, a, ua), (_, b, ub))| (mask, a & b, ua || ub))
+            .collect();
+        if axes.iter().any(|(_, axis, _)| *axis == 0) {
+            return None;
+        }
+        Some(from_axes(axes.into_iter()))
+    }
+
+    /// The forms of this set which aren't in the other, or `None` if there are none.
+    /// Where the other cuts a corner out of this one (they differ on more than one axis),
+    /// what is left isn't a set of this kind, and the smallest set holding it, this one,
+    /// is given.
+    pub fn difference(&self, other: &HcParameterSet) -> Option<HcParameterSet> {
+        if self.intersection(other).is_none() {
+            return Some(*self);
+        }
+        let axes: Vec<(u32, u32, bool, u32)> = axis_bits(self.bits)
+            .zip(axis_bits(other.bits))
+            .map(|((mask, a, ua), (_, b, _))| (mask, a, ua, b))
+            .collect();
+        // the axes on which this set has values which the other doesn't
+        let wider: Vec<usize> = (0..axes.len())
+            .filter(|i| axes[*i].1 & !axes[*i].3 != 0)
+            .collect();
+        match wider[..] {
+            [] => None,
+            [i] => Some(from_axes(axes.iter().enumerate().map(
+                |(j, (mask, a, ua, b))| {
+                    if i == j {
+                        (*mask, a & !b, false)
+                    } else {
+                        (*mask, *a, *ua)
+                    }
+                },
+            ))),
+            _ => Some(*self),
+        }
+    }
+}
+
+/// `union()`
+impl BitOr for HcParameterSet {
+    type Output = HcParameterSet;
+    fn bitor(self, other: HcParameterSet) -> HcParameterSet {
+        self.union(&other)
+    }
+}
+
+/// `intersection()`
+impl BitAnd for HcParameterSet {
+    type Output = Option<HcParameterSet>;
+    fn bitand(self, other: HcParameterSet) -> Option<HcParameterSet> {
+        self.intersection(&other)
+    }
+}
+
+/// `difference()`
+impl Sub for HcParameterSet {
+    type Output = Option<HcParameterSet>;
+    fn sub(self, other: HcParameterSet) -> Option<HcParameterSet> {
+        self.difference(&other)
+    }
+}
+
+impl FromStr for HcParameterSet {
+    type Err = String;
+
+    /// abbreviations, or the full names of the values (e.g. "Aorist"), in any case
+    fn from_str(s: &str) -> Result<HcParameterSet, String> {
+        let full_names: [Vec<&str>; 7] = [
+            PERSONS.iter().map(|v| v.value()).collect(),
+            NUMBERS.iter().map(|v| v.value()).collect(),
+            TENSES.iter().map(|v| v.value()).collect(),
+            VOICES.iter().map(|v| v.value()).collect(),
+            MOODS.iter().map(|v| v.value()).collect(),
+            GENDERS.iter().map(|v| v.value()).collect(),
+            CASES.iter().map(|v| v.value()).collect(),
+        ];
+        let mut bits = 0;
+        for token in s.split([',', ';']).map(str::trim).filter(|t| !t.is_empty()) {
+            let bit = AXES
+                .iter()
+                .zip(full_names.iter())
+                .find_map(|((first, names), full)| {
+                    names
+                        .iter()
+                        .zip(full.iter())
+                        .position(|(abbrev, name)| {
+                            token.eq_ignore_ascii_case(abbrev)
+                                || (name.len() > 1 && token.eq_ignore_ascii_case(name))
+                        })
+                        .map(|i| first + i as u32)
+                });
+            match bit {
+                Some(bit) => bits |= 1 << bit,
+                None => return Err(format!("unknown parameter: {}", token)),
+            }
+        }
+        Ok(HcParameterSet { bits })
+    }
+}
+
+impl fmt::Display for HcParameterSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let groups: Vec<String> = AXES
+            .iter()
+            .map(|(first, names)| {
+                names
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| self.bits & (1 << (first + *i as u32)) != 0)
+                    .map(|(_, name)| *name)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .filter(|g| !g.is_empty())
+            .collect();
+        write!(f, "{}", groups.join("; "))
+    }
+}
+
+/// the set holding exactly a form's parameters
+impl From<&HcGreekVerbForm> for HcParameterSet {
+    fn from(vf: &HcGreekVerbForm) -> HcParameterSet {
+        let mut set = HcParameterSet::new()
+            .with_tense(vf.tense)
+            .with_voice(vf.voice)
+            .with_mood(vf.mood);
+        if let Some(person) = vf.person {
+            set = set.with_person(person);
+        }
+        if let Some(number) = vf.number {
+            set = set.with_number(number);
+        }
+        if let Some(gender) = vf.gender {
+            set = set.with_gender(gender);
+        }
+        if let Some(case) = vf.case {
+            set = set.with_case(case);
+        }
+        set
+    }
+}
+
+impl From<&VerbParameters> for HcParameterSet {
+    fn from(params: &VerbParameters) -> HcParameterSet {
+        let mut set = HcParameterSet::new();
+        set = params.persons.iter().fold(set, |s, p| s.with_person(*p));
+        set = params.numbers.iter().fold(set, |s, n| s.with_number(*n));
+        set = params.tenses.iter().fold(set, |s, t| s.with_tense(*t));
+        set = params.voices.iter().fold(set, |s, v| s.with_voice(*v));
+        params.moods.iter().fold(set, |s, m| s.with_mood(*m))
+    }
+}
@@ -0,0 +1,357 @@
+//! The `hoplite_verbs_rs` Python extension module, built with `maturin develop` or
+//! `maturin build` (see pyproject.toml).
+//!
+//! Parameters are the enums exported to Python (`Tense.Aorist`, ...) or strings: either
+//! the enum's `value()` ("3", "s", "Aorist") or its name ("Third", "Singular"), ignoring
+//! case.  An `HcFormError` is raised as a subclass of `FormError` with the same name as
+//! the error's code, e.g. `BlankPrincipalPart`, the message giving the reason.
+
+use super::*;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyIOError, PyValueError};
+use pyo3::prelude::*;
+use std::fmt::Debug;
+use std::io::BufReader;
+
+create_exception!(hoplite_verbs_rs, FormError, PyException);
+create_exception!(hoplite_verbs_rs, InternalError, FormError);
+create_exception!(hoplite_verbs_rs, BlankPrincipalPart, FormError);
+create_exception!(hoplite_verbs_rs, InvalidPrincipalPart, FormError);
+create_exception!(hoplite_verbs_rs, DeponentNoFormForVoice, FormError);
+create_exception!(hoplite_verbs_rs, IllegalForm, FormError);
+create_exception!(hoplite_verbs_rs, DoesNotExist, FormError);
+create_exception!(hoplite_verbs_rs, NoFormForUnit, FormError);
+create_exception!(hoplite_verbs_rs, NotImplemented, FormError);
+
+fn form_error(e: HcFormError) -> PyErr {
+    let msg = e.to_string();
+    match e {
+        HcFormError::InternalError(_) => InternalError::new_err(msg),
+        HcFormError::BlankPrincipalPartForForm(_) => BlankPrincipalPart::new_err(msg),
+        HcFormError::UnexpectedPrincipalPartEnding { .. } => InvalidPrincipalPart::new_err(msg),
+        HcFormError::Deponent { .. } => DeponentNoFormForVoice::new_err(msg),
+        HcFormError::IllegalForm { .. } => IllegalForm::new_err(msg),
+        HcFormError::DoesNotExist => DoesNotExist::new_err(msg),
+        HcFormError::NotAvailableInUnit => NoFormForUnit::new_err(msg),
+        HcFormError::NotImplemented => NotImplemented::new_err(msg),
+    }
+}
+
+// an exported enum, or a string matching a variant's value() or name
+fn extract_param<T>(obj: &Bound<'_, PyAny>, values: &[T], value: fn(&T) -> &str) -> PyResult<T>
+where
+    T: Copy + Debug + for<'py> FromPyObject<'py>,
+{
+    if let Ok(v) = obj.extract::<T>() {
+        return Ok(v);
+    }
+    let s: String = obj.extract()?;
+    values
+        .iter()
+        .find(|v| value(v).eq_ignore_ascii_case(&s) || format!("{:?}", v).eq_ignore_ascii_case(&s))
+        .copied()
+        .ok_or_else(|| PyValueError::new_err(format!("unknown parameter: {}", s)))
+}
+
+fn extract_optional<T>(
+    obj: Option<&Bound<'_, PyAny>>,
+    values: &[T],
+    value: fn(&T) -> &str,
+) -> PyResult<Option<T>>
+where
+    T: Copy + Debug + for<'py> FromPyObject<'py>,
+{
+    match obj {
+        Some(o) if !o.is_none() => extract_param(o, values, value).map(Some),
+        _ => Ok(None),
+    }
+}
+
+static PERSONS: [HcPerson; 3] = [HcPerson::First, HcPerson::Second, HcPerson::Third];
+static NUMBERS: [HcNumber; 3] = [HcNumber::Singular, HcNumber::Dual, HcNumber::Plural];
+static TENSES: [HcTense; 6] = [
+    HcTense::Present,
+    HcTense::Imperfect,
+    HcTense::Future,
+    HcTense::Aorist,
+    HcTense::Perfect,
+    HcTense::Pluperfect,
+];
+static VOICES: [HcVoice; 3] = [HcVoice::Active, HcVoice::Middle, HcVoice::Passive];
+static MOODS: [HcMood; 6] = [
+    HcMood::Indicative,
+    HcMood::Subjunctive,
+    HcMood::Optative,
+    HcMood::Imperative,
+    HcMood::Infinitive,
+    HcMood::Participle,
+];
+static GENDERS: [HcGender; 3] = [HcGender::Masculine, HcGender::Feminine, HcGender::Neuter];
+static CASES: [HcCase; 5] = [
+    HcCase::Nominative,
+    HcCase::Genitive,
+    HcCase::Dative,
+    HcCase::Accusative,
+    HcCase::Vocative,
+];
+static DIALECTS: [HcDialect; 4] = [
+    HcDialect::Attic,
+    HcDialect::Ionic,
+    HcDialect::Homeric,
+    HcDialect::Koine,
+];
+
+/// A verb and its six principal parts.
+#[pyclass(name = "Verb", frozen)]
+#[derive(Clone)]
+struct PyVerb {
+    verb: Arc<HcGreekVerb>,
+}
+
+#[pymethods]
+impl PyVerb {
+    /// `line` is a line of pp.txt: the principal parts, then optionally %unit%properties
+    #[new]
+    #[pyo3(signature = (id, line))]
+    fn new(id: u32, line: &str) -> PyResult<PyVerb> {
+        HcGreekVerb::from_string_with_properties(id, line)
+            .map(|verb| PyVerb {
+                verb: Arc::new(verb),
+            })
+            .ok_or_else(|| PyValueError::new_err("expected six principal parts"))
+    }
+
+    #[getter]
+    fn id(&self) -> u32 {
+        self.verb.id
+    }
+
+    #[getter]
+    fn pps(&self) -> Vec<String> {
+        self.verb.pps.clone()
+    }
+
+    #[getter]
+    fn lemma(&self) -> String {
+        self.verb.get_verb_lemma()
+    }
+
+    #[getter]
+    fn hq_unit(&self) -> u32 {
+        self.verb.hq_unit
+    }
+
+    #[getter]
+    fn properties(&self) -> u32 {
+        self.verb.properties
+    }
+
+    /// compares a comma separated list of six principal parts with the verb's, under a
+    /// grading profile (strict, standard or lenient)
+    #[pyo3(signature = (input, profile=None))]
+    fn check_pps(&self, input: &str, profile: Option<&str>) -> PyResult<Vec<bool>> {
+        Ok(grading::grade_pps(input, &self.verb, &grading_profile(profile)?).correct)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Verb({}, {:?})", self.verb.id, self.verb.pps.join(", "))
+    }
+}
+
+/// One step of a form's derivation.
+#[pyclass(name = "Step", frozen, get_all)]
+struct PyStep {
+    form: String,
+    explanation: String,
+}
+
+#[pymethods]
+impl PyStep {
+    fn __repr__(&self) -> String {
+        format!("Step({:?}, {:?})", self.form, self.explanation)
+    }
+}
+
+/// A verb with its person, number, tense, voice and mood (or gender and case).
+#[pyclass(name = "VerbForm", frozen)]
+#[derive(Clone)]
+struct PyVerbForm {
+    vf: HcGreekVerbForm,
+}
+
+#[pymethods]
+impl PyVerbForm {
+    #[new]
+    #[pyo3(signature = (verb, tense, voice, mood, person=None, number=None, gender=None, case=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        verb: &PyVerb,
+        tense: &Bound<'_, PyAny>,
+        voice: &Bound<'_, PyAny>,
+        mood: &Bound<'_, PyAny>,
+        person: Option<&Bound<'_, PyAny>>,
+        number: Option<&Bound<'_, PyAny>>,
+        gender: Option<&Bound<'_, PyAny>>,
+        case: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<PyVerbForm> {
+        Ok(PyVerbForm {
+            vf: HcGreekVerbForm {
+                verb: verb.verb.clone(),
+                person: extract_optional(person, &PERSONS, |v| v.value())?,
+                number: extract_optional(number, &NUMBERS, |v| v.value())?,
+                tense: extract_param(tense, &TENSES, |v| v.value())?,
+                voice: extract_param(voice, &VOICES, |v| v.value())?,
+                mood: extract_param(mood, &MOODS, |v| v.value())?,
+                gender: extract_optional(gender, &GENDERS, |v| v.value())?,
+                case: extract_optional(case, &CASES, |v| v.value())?,
+            },
+        })
+    }
+
+    #[getter]
+    fn verb(&self) -> PyVerb {
+        PyVerb {
+            verb: self.vf.verb.clone(),
+        }
+    }
+
+    #[getter]
+    fn person(&self) -> Option<HcPerson> {
+        self.vf.person
+    }
+
+    #[getter]
+    fn number(&self) -> Option<HcNumber> {
+        self.vf.number
+    }
+
+    #[getter]
+    fn tense(&self) -> HcTense {
+        self.vf.tense
+    }
+
+    #[getter]
+    fn voice(&self) -> HcVoice {
+        self.vf.voice
+    }
+
+    #[getter]
+    fn mood(&self) -> HcMood {
+        self.vf.mood
+    }
+
+    #[getter]
+    fn gender(&self) -> Option<HcGender> {
+        self.vf.gender
+    }
+
+    #[getter]
+    fn case(&self) -> Option<HcCase> {
+        self.vf.case
+    }
+
+    /// the derivation of the form, the form itself being the last step's
+    #[pyo3(signature = (decompose=false, extended_perfect=false, dialect=None))]
+    fn get_form(
+        &self,
+        decompose: bool,
+        extended_perfect: bool,
+        dialect: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Vec<PyStep>> {
+        let options = HcFormOptions {
+            extended_perfect,
+            dialect: extract_optional(dialect, &DIALECTS, |v| v.value())?.unwrap_or_default(),
+        };
+        self.vf
+            .get_form_with_options(decompose, &options)
+            .map(|steps| {
+                steps
+                    .into_iter()
+                    .map(|s| PyStep {
+                        form: s.form,
+                        explanation: s.explanation,
+                    })
+                    .collect()
+            })
+            .map_err(form_error)
+    }
+
+    fn is_legal_form(&self) -> bool {
+        self.vf.is_legal_form()
+    }
+
+    /// describes the form, wrapping the parameters which differ from prev in start and end
+    #[pyo3(signature = (prev=None, start="", end=""))]
+    fn get_description(&self, prev: Option<&PyVerbForm>, start: &str, end: &str) -> String {
+        self.vf
+            .get_description(prev.map_or(&self.vf, |p| &p.vf), start, end)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "VerbForm({}, {})",
+            self.vf.verb.pps[0],
+            self.vf.get_description(&self.vf, "", "")
+        )
+    }
+}
+
+/// loads a pp.txt style lexicon, the verb ids being the line numbers from 0
+#[pyfunction]
+fn load_lexicon(path: &str) -> PyResult<Vec<PyVerb>> {
+    let file = std::fs::File::open(path).map_err(|e| PyIOError::new_err(e.to_string()))?;
+    let verbs = read_lexicon(BufReader::new(file)).map_err(|e| match e.kind() {
+        std::io::ErrorKind::InvalidData => PyValueError::new_err(e.to_string()),
+        _ => PyIOError::new_err(e.to_string()),
+    })?;
+    Ok(verbs.into_iter().map(|verb| PyVerb { verb }).collect())
+}
+
+#[pyfunction(name = "check_pps")]
+#[pyo3(signature = (input, verb, profile=None))]
+fn py_check_pps(input: &str, verb: &PyVerb, profile: Option<&str>) -> PyResult<Vec<bool>> {
+    verb.check_pps(input, profile)
+}
+
+fn grading_profile(name: Option<&str>) -> PyResult<grading::HcGradingProfile> {
+    match name {
+        Some(name) => grading::HcGradingProfile::from_name(name)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown grading profile: {}", name))),
+        None => Ok(grading::HcGradingProfile::default()),
+    }
+}
+
+#[pymodule]
+fn hoplite_verbs_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<HcPerson>()?;
+    m.add_class::<HcNumber>()?;
+    m.add_class::<HcTense>()?;
+    m.add_class::<HcVoice>()?;
+    m.add_class::<HcMood>()?;
+    m.add_class::<HcGender>()?;
+    m.add_class::<HcCase>()?;
+    m.add_class::<HcDialect>()?;
+    m.add_class::<PyVerb>()?;
+    m.add_class::<PyStep>()?;
+    m.add_class::<PyVerbForm>()?;
+    m.add_function(wrap_pyfunction!(load_lexicon, m)?)?;
+    m.add_function(wrap_pyfunction!(py_check_pps, m)?)?;
+
+    let py = m.py();
+    m.add("FormError", py.get_type::<FormError>())?;
+    m.add("InternalError", py.get_type::<InternalError>())?;
+    m.add("BlankPrincipalPart", py.get_type::<BlankPrincipalPart>())?;
+    m.add(
+        "InvalidPrincipalPart",
+        py.get_type::<InvalidPrincipalPart>(),
+    )?;
+    m.add(
+        "DeponentNoFormForVoice",
+        py.get_type::<DeponentNoFormForVoice>(),
+    )?;
+    m.add("IllegalForm", py.get_type::<IllegalForm>())?;
+    m.add("DoesNotExist", py.get_type::<DoesNotExist>())?;
+    m.add("NoFormForUnit", py.get_type::<NoFormForUnit>())?;
+    m.add("NotImplemented", py.get_type::<NotImplemented>())?;
+    Ok(())
+}
@@ -0,0 +1,449 @@
+//! JSON endpoints for hoplite-server.  Requests are handled by [`HcServer::handle`], which
+//! doesn't touch the network, so clients and tests can also call it in-process.
+//!
+//! All endpoints take a POST with a JSON body:
+//!
+//! - `/generate`: `{"verb": ..., "form": FormSpec, "decompose": false, "options": ...}`
+//!   returns `{"form": "...", "steps": [{"form": "...", "explanation": "..."}]}`
+//! - `/paradigm`: `{"verb": ..., "options": ...}` returns the finite forms and infinitives
+//! - `/quiz/next`: `{"params": "6,9,17", "max_unit": 5, "seed": 1}` returns a verb and a
+//!   FormSpec to produce; `params` is the string taken by `VerbParameters::from_option`
+//! - `/quiz/grade`: `{"verb": ..., "form": FormSpec, "answer": "...", "profile": "lenient"}`
+//!   returns `{"correct": true, "answer": "...", "correct_answer": "...", "profile": "...",
+//!   "normalizations": ["FinalSigma"]}`; `profile` is one of `grading::HcGradingProfile`'s
+//!   names, standard if not given, and `normalizations` lists what `input::normalize_input`
+//!   changed in the answer, if anything
+//!
+//! `verb` is the verb's id (its line number in the lexicon) or its first principal part.
+//! A FormSpec uses the `value()` strings of the parameter enums, e.g.
+//! `{"person": "3", "number": "p", "tense": "Aorist", "voice": "Middle", "mood": "Optative"}`.
+//! Errors are `{"error": "..."}` with status 400 (bad request), 404 (unknown verb or
+//! endpoint) or 422 (no such form, the error being `HcFormError`'s code, with its reason
+//! in `"reason"`).
+
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::io::BufRead;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum VerbRef {
+    Id(u32),
+    Lemma(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FormSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub person: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
+    pub tense: String,
+    pub voice: String,
+    pub mood: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gender: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct OptionsSpec {
+    #[serde(default)]
+    pub extended_perfect: bool,
+    /// "Attic" (the default), "Ionic", "Homeric" or "Koine"
+    #[serde(default)]
+    pub dialect: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GenerateRequest {
+    pub verb: VerbRef,
+    pub form: FormSpec,
+    #[serde(default)]
+    pub decompose: bool,
+    #[serde(default)]
+    pub options: OptionsSpec,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct StepResponse {
+    pub form: String,
+    pub explanation: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct GenerateResponse {
+    pub form: String,
+    pub steps: Vec<StepResponse>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ParadigmRequest {
+    pub verb: VerbRef,
+    #[serde(default)]
+    pub options: OptionsSpec,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ParadigmForm {
+    pub form: FormSpec,
+    /// None if the form does not exist
+    pub result: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ParadigmResponse {
+    pub verb_id: u32,
+    pub principal_parts: Vec<String>,
+    pub forms: Vec<ParadigmForm>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct QuizRequest {
+    #[serde(default)]
+    pub params: Option<String>,
+    /// only verbs introduced in this H&Q unit or earlier
+    #[serde(default)]
+    pub max_unit: Option<u32>,
+    /// the same seed gives the same question; a seed is made up if there is none
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct QuizResponse {
+    pub verb_id: u32,
+    pub lemma: String,
+    pub principal_parts: Vec<String>,
+    pub form: FormSpec,
+    pub prompt: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GradeRequest {
+    pub verb: VerbRef,
+    pub form: FormSpec,
+    pub answer: String,
+    /// a grading profile's name; standard if not given
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct GradeResponse {
+    pub correct: bool,
+    pub answer: String,
+    pub correct_answer: String,
+    pub profile: String,
+    /// `input::HcNormalization` values, what was changed in the answer before grading
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub normalizations: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ErrorResponse {
+    pub error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct HcResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HcResponse {
+    fn json<T: Serialize>(status: u16, value: &T) -> HcResponse {
+        HcResponse {
+            status,
+            body: serde_json::to_string(value).unwrap(),
+        }
+    }
+
+    fn error(status: u16, error: &str) -> HcResponse {
+        HcResponse::json(
+            status,
+            &ErrorResponse {
+                error: error.to_string(),
+                reason: None,
+            },
+        )
+    }
+
+    fn form_error(e: HcFormError) -> HcResponse {
+        HcResponse::json(
+            422,
+            &ErrorResponse {
+                error: e.code().to_string(),
+                reason: Some(e.to_string()),
+            },
+        )
+    }
+}
+
+pub struct HcServer {
+    verbs: Vec<Arc<HcGreekVerb>>,
+}
+
+impl HcServer {
+    pub fn new(verbs: Vec<Arc<HcGreekVerb>>) -> HcServer {
+        HcServer { verbs }
+    }
+
+    /// loads a pp.txt style lexicon, the verb ids being the line numbers from 0
+    pub fn from_lexicon<R: BufRead>(reader: R) -> Result<HcServer, String> {
+        let verbs = read_lexicon(reader).map_err(|e| e.to_string())?;
+        Ok(HcServer { verbs })
+    }
+
+    /// `path` is the request's URL as sent, whose query string, if any, is ignored
+    pub fn handle(&self, method: &str, path: &str, body: &str) -> HcResponse {
+        if method != "POST" {
+            return HcResponse::error(405, "endpoints only accept POST");
+        }
+        let result = match path.split('?').next().unwrap_or(path) {
+            "/generate" => parse_body(body).and_then(|r| self.generate(r)),
+            "/paradigm" => parse_body(body).and_then(|r| self.paradigm(r)),
+            "/quiz/next" => parse_body(body).and_then(|r| self.next_question(r)),
+            "/quiz/grade" => parse_body(body).and_then(|r| self.grade(r)),
+            _ => Err(HcResponse::error(404, "unknown endpoint")),
+        };
+        result.unwrap_or_else(|e| e)
+    }
+
+    fn find_verb(&self, verb: &VerbRef) -> Result<&Arc<HcGreekVerb>, HcResponse> {
+        match verb {
+            VerbRef::Id(id) => self.verbs.iter().find(|v| v.id == *id),
+            VerbRef::Lemma(lemma) => self.verbs.iter().find(|v| v.pps[0] == *lemma),
+        }
+        .ok_or_else(|| HcResponse::error(404, "unknown verb"))
+    }
+
+    fn generate(&self, request: GenerateRequest) -> Result<HcResponse, HcResponse> {
+        let vf = form_from_spec(self.find_verb(&request.verb)?, &request.form)?;
+        let options = options_from_spec(&request.options)?;
+        match vf.get_form_with_options(request.decompose, &options) {
+            Ok(steps) => Ok(HcResponse::json(
+                200,
+                &GenerateResponse {
+                    form: steps.last().unwrap().form.to_string(),
+                    steps: steps
+                        .into_iter()
+                        .map(|s| StepResponse {
+                            form: s.form,
+                            explanation: s.explanation,
+                        })
+                        .collect(),
+                },
+            )),
+            Err(e) => Err(HcResponse::form_error(e)),
+        }
+    }
+
+    fn paradigm(&self, request: ParadigmRequest) -> Result<HcResponse, HcResponse> {
+        let verb = self.find_verb(&request.verb)?;
+        let options = options_from_spec(&request.options)?;
+
+        let mut forms = Vec::new();
+        for vf in paradigm_forms(verb) {
+            let result = vf
+                .get_form_with_options(false, &options)
+                .ok()
+                .map(|steps| steps.last().unwrap().form.to_string());
+            forms.push(ParadigmForm {
+                form: spec_from_form(&vf),
+                result,
+            });
+        }
+        Ok(HcResponse::json(
+            200,
+            &ParadigmResponse {
+                verb_id: verb.id,
+                principal_parts: verb.pps.clone(),
+                forms,
+            },
+        ))
+    }
+
+    fn next_question(&self, request: QuizRequest) -> Result<HcResponse, HcResponse> {
+        let params = VerbParameters::from_option(request.params);
+        let verbs: Vec<&Arc<HcGreekVerb>> = self
+            .verbs
+            .iter()
+            .filter(|v| request.max_unit.is_none_or(|unit| v.hq_unit <= unit))
+            .collect();
+        if verbs.is_empty() {
+            return Err(HcResponse::error(422, "no verbs in these units"));
+        }
+
+        let mut rng = SplitMix64(request.seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0)
+        }));
+
+        // most random combinations exist, so this almost never needs many tries
+        for _ in 0..1000 {
+            let vf = HcGreekVerbForm {
+                verb: (*rng.choose(&verbs)).clone(),
+                person: Some(*rng.choose(&params.persons)),
+                number: Some(*rng.choose(&params.numbers)),
+                tense: *rng.choose(&params.tenses),
+                voice: *rng.choose(&params.voices),
+                mood: *rng.choose(&params.moods),
+                gender: None,
+                case: None,
+            };
+            if vf.get_form(false).is_err() {
+                continue;
+            }
+            return Ok(HcResponse::json(
+                200,
+                &QuizResponse {
+                    verb_id: vf.verb.id,
+                    lemma: vf.verb.pps[0].to_string(),
+                    principal_parts: vf.verb.pps.clone(),
+                    form: spec_from_form(&vf),
+                    prompt: format!(
+                        "{} {} {} {} {} of {}",
+                        vf.person.unwrap().value(),
+                        vf.number.unwrap().value(),
+                        vf.tense.value(),
+                        vf.voice.value(),
+                        vf.mood.value(),
+                        vf.verb.pps[0]
+                    ),
+                },
+            ));
+        }
+        Err(HcResponse::error(422, "no form found for these parameters"))
+    }
+
+    fn grade(&self, request: GradeRequest) -> Result<HcResponse, HcResponse> {
+        let vf = form_from_spec(self.find_verb(&request.verb)?, &request.form)?;
+        let profile = match request.profile.as_deref() {
+            Some(name) => grading::HcGradingProfile::from_name(name).ok_or_else(|| {
+                HcResponse::error(400, &format!("unknown grading profile: {}", name))
+            })?,
+            None => grading::HcGradingProfile::default(),
+        };
+        let grade =
+            grading::grade_form(&vf, &request.answer, &profile).map_err(HcResponse::form_error)?;
+        Ok(HcResponse::json(
+            200,
+            &GradeResponse {
+                correct: grade.correct,
+                answer: request.answer,
+                correct_answer: grade.correct_answer,
+                profile: grade.profile,
+                normalizations: grade
+                    .normalizations
+                    .iter()
+                    .map(|n| n.value().to_string())
+                    .collect(),
+            },
+        ))
+    }
+}
+
+fn parse_body<T: for<'a> Deserialize<'a>>(body: &str) -> Result<T, HcResponse> {
+    serde_json::from_str(body).map_err(|e| HcResponse::error(400, &e.to_string()))
+}
+
+fn spec_from_form(vf: &HcGreekVerbForm) -> FormSpec {
+    FormSpec {
+        person: vf.person.map(|p| p.value().to_string()),
+        number: vf.number.map(|n| n.value().to_string()),
+        tense: vf.tense.value().to_string(),
+        voice: vf.voice.value().to_string(),
+        mood: vf.mood.value().to_string(),
+        gender: vf.gender.map(|g| g.value().to_string()),
+        case: vf.case.map(|c| c.value().to_string()),
+    }
+}
+
+// looks a value up by the value() string of each variant
+fn parse_value<T: Copy>(
+    values: &[T],
+    value: impl Fn(&T) -> &str,
+    s: &str,
+    name: &str,
+) -> Result<T, HcResponse> {
+    values
+        .iter()
+        .find(|v| value(v) == s)
+        .copied()
+        .ok_or_else(|| HcResponse::error(400, &format!("unknown {}: {}", name, s)))
+}
+
+fn form_from_spec(verb: &Arc<HcGreekVerb>, spec: &FormSpec) -> Result<HcGreekVerbForm, HcResponse> {
+    let persons = [HcPerson::First, HcPerson::Second, HcPerson::Third];
+    let numbers = [HcNumber::Singular, HcNumber::Dual, HcNumber::Plural];
+    let tenses = [
+        HcTense::Present,
+        HcTense::Imperfect,
+        HcTense::Future,
+        HcTense::Aorist,
+        HcTense::Perfect,
+        HcTense::Pluperfect,
+    ];
+    let voices = [HcVoice::Active, HcVoice::Middle, HcVoice::Passive];
+    let moods = [
+        HcMood::Indicative,
+        HcMood::Subjunctive,
+        HcMood::Optative,
+        HcMood::Imperative,
+        HcMood::Infinitive,
+        HcMood::Participle,
+    ];
+    let genders = [HcGender::Masculine, HcGender::Feminine, HcGender::Neuter];
+    let cases = [
+        HcCase::Nominative,
+        HcCase::Genitive,
+        HcCase::Dative,
+        HcCase::Accusative,
+        HcCase::Vocative,
+    ];
+
+    Ok(HcGreekVerbForm {
+        verb: verb.clone(),
+        person: match &spec.person {
+            Some(p) => Some(parse_value(&persons, |v| v.value(), p, "person")?),
+            None => None,
+        },
+        number: match &spec.number {
+            Some(n) => Some(parse_value(&numbers, |v| v.value(), n, "number")?),
+            None => None,
+        },
+        tense: parse_value(&tenses, |v| v.value(), &spec.tense, "tense")?,
+        voice: parse_value(&voices, |v| v.value(), &spec.voice, "voice")?,
+        mood: parse_value(&moods, |v| v.value(), &spec.mood, "mood")?,
+        gender: match &spec.gender {
+            Some(g) => Some(parse_value(&genders, |v| v.value(), g, "gender")?),
+            None => None,
+        },
+        case: match &spec.case {
+            Some(c) => Some(parse_value(&cases, |v| v.value(), c, "case")?),
+            None => None,
+        },
+    })
+}
+
+fn options_from_spec(spec: &OptionsSpec) -> Result<HcFormOptions, HcResponse> {
+    let dialects = [
+        HcDialect::Attic,
+        HcDialect::Ionic,
+        HcDialect::Homeric,
+        HcDialect::Koine,
+    ];
+    Ok(HcFormOptions {
+        extended_perfect: spec.extended_perfect,
+        dialect: match &spec.dialect {
+            Some(d) => parse_value(&dialects, |v| v.value(), d, "dialect")?,
+            None => HcDialect::Attic,
+        },
+    })
+}
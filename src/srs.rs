@@ -0,0 +1,371 @@
+//! Spaced repetition of verb forms and principal parts, for students drilling the same
+//! lexicon over a year.
+//!
+//! Each item, a (verb, form) or (verb, principal part), is scheduled with SM-2: a correct
+//! answer pushes the next review further out (1 day, 6 days, then the last interval times
+//! the item's ease), a wrong one brings it back to tomorrow and lowers its ease.  Reviews
+//! are recorded from grades, not booleans: a correct answer is quality 5, or 4 if the
+//! answer had to be normalized first, and a wrong one is quality 1.  A blank written `---`
+//! is not held against an answer, and of the principal parts only those which had to be
+//! normalized themselves count for less.
+//!
+//! Only verbs of units up to `max_unit` (`HcGreekVerb::hq_unit`) are scheduled.  When
+//! nothing is due, an item not seen before is introduced, by unit then lexicon order, the
+//! principal parts of a verb before its forms, up to `new_per_day` a day.
+//!
+//! Times are seconds since the Unix epoch, given by the caller.  The state is saved as JSON:
+//!
+//! ```text
+//! {"version": 1, "max_unit": 5, "parameters": "aor; act", "new_per_day": 20, "items": [
+//!   {"verb": 0, "principal_part": 3, "repetitions": 2, "interval": 6, "ease": 2.6,
+//!    "due": 1700000000, "lapses": 0, "introduced": 1699000000},
+//!   {"verb": 0, "form": "3; sg; aor; act; ind", ...}]}
+//! ```
+
+use super::*;
+use grading::{HcGrade, HcPrincipalPartsGrade};
+use parameters::HcParameterSet;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+
+const VERSION: u32 = 1;
+const DAY: u64 = 24 * 60 * 60;
+const INITIAL_EASE: f64 = 2.5;
+const MINIMUM_EASE: f64 = 1.3;
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+pub enum HcReviewItem {
+    Form {
+        verb_id: u32,
+        /// exactly the form's parameters, see `HcParameterSet::to_form`
+        parameters: HcParameterSet,
+    },
+    PrincipalPart {
+        verb_id: u32,
+        part: HcGreekPrincipalParts,
+    },
+}
+
+impl HcReviewItem {
+    pub fn verb_id(&self) -> u32 {
+        match self {
+            HcReviewItem::Form { verb_id, .. } | HcReviewItem::PrincipalPart { verb_id, .. } => {
+                *verb_id
+            }
+        }
+    }
+
+    /// the form to ask for, if the item is a form
+    pub fn to_form(&self, verb: &Arc<HcGreekVerb>) -> Option<HcGreekVerbForm> {
+        match self {
+            HcReviewItem::Form { parameters, .. } => parameters.to_form(verb),
+            HcReviewItem::PrincipalPart { .. } => None,
+        }
+    }
+
+    // a verb's principal parts first, then its forms
+    fn sort_key(&self) -> (u32, u32, u32) {
+        match self {
+            HcReviewItem::PrincipalPart { verb_id, part } => (*verb_id, 0, *part as u32),
+            HcReviewItem::Form {
+                verb_id,
+                parameters,
+            } => (*verb_id, 1, parameters.bits()),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct HcReviewState {
+    /// correct reviews in a row
+    pub repetitions: u32,
+    /// days from the last review to the next
+    pub interval: u32,
+    pub ease: f64,
+    pub due: u64,
+    /// how often it was answered wrongly after having been learned
+    pub lapses: u32,
+    /// when it was first reviewed
+    pub introduced: u64,
+}
+
+impl HcReviewState {
+    fn new(now: u64) -> HcReviewState {
+        HcReviewState {
+            repetitions: 0,
+            interval: 0,
+            ease: INITIAL_EASE,
+            due: now,
+            lapses: 0,
+            introduced: now,
+        }
+    }
+
+    // SM-2, quality being 0 (no idea) to 5 (perfect)
+    fn review(&mut self, quality: u8, now: u64) {
+        let q = f64::from(quality.min(5));
+        if quality >= 3 {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (f64::from(self.interval) * self.ease).round() as u32,
+            };
+            self.repetitions += 1;
+        } else {
+            if self.repetitions > 0 {
+                self.lapses += 1;
+            }
+            self.repetitions = 0;
+            self.interval = 1;
+        }
+        self.ease = (self.ease + 0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02)).max(MINIMUM_EASE);
+        self.due = now + u64::from(self.interval) * DAY;
+    }
+}
+
+fn quality(correct: bool, normalizations: &[input::HcNormalization]) -> u8 {
+    // --- is how a blank is typed, not a slip
+    let normalized = normalizations
+        .iter()
+        .any(|n| *n != input::HcNormalization::Blank);
+    match (correct, !normalized) {
+        (false, _) => 1,
+        (true, false) => 4,
+        (true, true) => 5,
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct HcScheduler {
+    /// verbs of later units are not scheduled
+    pub max_unit: u32,
+    /// the forms which are drilled; the empty set is every form
+    pub parameters: HcParameterSet,
+    /// how many items not seen before may be introduced in a day
+    pub new_per_day: usize,
+    items: HashMap<HcReviewItem, HcReviewState>,
+}
+
+impl HcScheduler {
+    pub fn new(max_unit: u32) -> HcScheduler {
+        HcScheduler {
+            max_unit,
+            parameters: HcParameterSet::new(),
+            new_per_day: 20,
+            items: HashMap::new(),
+        }
+    }
+
+    pub fn state(&self, item: &HcReviewItem) -> Option<&HcReviewState> {
+        self.items.get(item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn review(&mut self, item: HcReviewItem, quality: u8, now: u64) {
+        self.items
+            .entry(item)
+            .or_insert_with(|| HcReviewState::new(now))
+            .review(quality, now);
+    }
+
+    pub fn review_form(&mut self, vf: &HcGreekVerbForm, grade: &HcGrade, now: u64) {
+        let item = HcReviewItem::Form {
+            verb_id: vf.verb.id,
+            parameters: HcParameterSet::from(vf),
+        };
+        self.review(item, quality(grade.correct, &grade.normalizations), now);
+    }
+
+    /// each principal part is its own item
+    pub fn review_pps(&mut self, verb: &HcGreekVerb, grade: &HcPrincipalPartsGrade, now: u64) {
+        for (i, (part, correct)) in PRINCIPAL_PARTS.iter().zip(&grade.correct).enumerate() {
+            let item = HcReviewItem::PrincipalPart {
+                verb_id: verb.id,
+                part: *part,
+            };
+            let normalizations = grade.part_normalizations.get(i).map_or(&[][..], |n| n);
+            self.review(item, quality(*correct, normalizations), now);
+        }
+    }
+
+    /// the items due by now of the verbs in `max_unit`'s units, the most overdue first
+    pub fn due(&self, verbs: &[Arc<HcGreekVerb>], now: u64) -> Vec<HcReviewItem> {
+        let introduced: HashSet<u32> = self.verbs(verbs).map(|v| v.id).collect();
+        let mut due: Vec<(&HcReviewItem, &HcReviewState)> = self
+            .items
+            .iter()
+            .filter(|(item, state)| state.due <= now && introduced.contains(&item.verb_id()))
+            .collect();
+        due.sort_by_key(|(item, state)| (state.due, item.sort_key()));
+        due.into_iter().map(|(item, _)| *item).collect()
+    }
+
+    /// what to review next: the most overdue item, else a new one if today's aren't used
+    /// up, else None
+    pub fn next(&self, verbs: &[Arc<HcGreekVerb>], now: u64) -> Option<HcReviewItem> {
+        if let Some(item) = self.due(verbs, now).first() {
+            return Some(*item);
+        }
+        let today = now / DAY;
+        let introduced_today = self
+            .items
+            .values()
+            .filter(|s| s.introduced / DAY == today)
+            .count();
+        if introduced_today >= self.new_per_day {
+            return None;
+        }
+        self.new_items(verbs).next()
+    }
+
+    // the verbs in max_unit's units, by unit then lexicon order
+    fn verbs<'a>(
+        &self,
+        verbs: &'a [Arc<HcGreekVerb>],
+    ) -> impl Iterator<Item = &'a Arc<HcGreekVerb>> {
+        let mut verbs: Vec<&Arc<HcGreekVerb>> = verbs
+            .iter()
+            .filter(|v| v.hq_unit <= self.max_unit)
+            .collect();
+        verbs.sort_by_key(|v| (v.hq_unit, v.id));
+        verbs.into_iter()
+    }
+
+    fn new_items<'a>(
+        &'a self,
+        verbs: &'a [Arc<HcGreekVerb>],
+    ) -> impl Iterator<Item = HcReviewItem> + 'a {
+        self.verbs(verbs).flat_map(move |verb| {
+            let pps = PRINCIPAL_PARTS
+                .iter()
+                .zip(&verb.pps)
+                .filter(|(_, pp)| pp.as_str() != "—")
+                .map(|(part, _)| HcReviewItem::PrincipalPart {
+                    verb_id: verb.id,
+                    part: *part,
+                });
+            // only a form not yet seen is generated, to see whether the verb has it
+            let forms = self
+                .parameters
+                .forms(verb)
+                .map(|vf| {
+                    let item = HcReviewItem::Form {
+                        verb_id: verb.id,
+                        parameters: HcParameterSet::from(&vf),
+                    };
+                    (item, vf)
+                })
+                .filter(|(item, vf)| !self.items.contains_key(item) && vf.get_form(false).is_ok())
+                .map(|(item, _)| item);
+            pps.filter(|item| !self.items.contains_key(item))
+                .chain(forms)
+        })
+    }
+
+    /// writes the items in order, so the same state always gives the same file
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut items: Vec<(&HcReviewItem, &HcReviewState)> = self.items.iter().collect();
+        items.sort_by_key(|(item, _)| item.sort_key());
+        let saved = SavedScheduler {
+            version: VERSION,
+            max_unit: self.max_unit,
+            parameters: self.parameters.to_string(),
+            new_per_day: self.new_per_day,
+            items: items
+                .into_iter()
+                .map(|(item, state)| {
+                    let (form, principal_part) = match item {
+                        HcReviewItem::Form { parameters, .. } => {
+                            (Some(parameters.to_string()), None)
+                        }
+                        HcReviewItem::PrincipalPart { part, .. } => (None, Some(*part as u8)),
+                    };
+                    SavedItem {
+                        verb: item.verb_id(),
+                        form,
+                        principal_part,
+                        state: state.clone(),
+                    }
+                })
+                .collect(),
+        };
+        serde_json::to_writer_pretty(&mut *w, &saved)?;
+        w.write_all(b"\n")
+    }
+
+    pub fn read<R: Read>(r: &mut R) -> io::Result<HcScheduler> {
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+        let saved: SavedScheduler = serde_json::from_reader(r)?;
+        if saved.version != VERSION {
+            return Err(invalid(format!(
+                "unknown scheduler version: {}",
+                saved.version
+            )));
+        }
+        let mut items = HashMap::with_capacity(saved.items.len());
+        for saved_item in saved.items {
+            let verb_id = saved_item.verb;
+            let item = match (saved_item.form, saved_item.principal_part) {
+                (Some(form), None) => HcReviewItem::Form {
+                    verb_id,
+                    parameters: form.parse().map_err(invalid)?,
+                },
+                (None, Some(part)) => HcReviewItem::PrincipalPart {
+                    verb_id,
+                    part: *PRINCIPAL_PARTS
+                        .get(usize::from(part).wrapping_sub(1))
+                        .ok_or_else(|| invalid(format!("no principal part {}", part)))?,
+                },
+                _ => {
+                    return Err(invalid(String::from(
+                        "an item needs one of form and principal_part",
+                    )))
+                }
+            };
+            items.insert(item, saved_item.state);
+        }
+        Ok(HcScheduler {
+            max_unit: saved.max_unit,
+            parameters: saved.parameters.parse().map_err(invalid)?,
+            new_per_day: saved.new_per_day,
+            items,
+        })
+    }
+}
+
+static PRINCIPAL_PARTS: [HcGreekPrincipalParts; 6] = [
+    HcGreekPrincipalParts::First,
+    HcGreekPrincipalParts::Second,
+    HcGreekPrincipalParts::Third,
+    HcGreekPrincipalParts::Fourth,
+    HcGreekPrincipalParts::Fifth,
+    HcGreekPrincipalParts::Sixth,
+];
+
+#[derive(Serialize, Deserialize)]
+struct SavedScheduler {
+    version: u32,
+    max_unit: u32,
+    parameters: String,
+    new_per_day: usize,
+    items: Vec<SavedItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedItem {
+    verb: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    form: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    principal_part: Option<u8>,
+    #[serde(flatten)]
+    state: HcReviewState,
+}
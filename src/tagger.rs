@@ -0,0 +1,397 @@
+//! Lemmatizing and tagging the verbs of running Greek text against a lexicon, by way of
+//! `HcFormIndex`.
+//!
+//! Before a word is looked up: grave accents become acute, the second accent added by a
+//! following enclitic is dropped, an elided vowel is restored (each of α ε ι ο is tried)
+//! and a crasis with καί is undone (κἀ, χἀ for καὶ ἐ/ἀ, καὶ ἑ/ἁ).  Movable ν needs
+//! nothing here, the index having both forms.  A word which doesn't match with its
+//! accents is looked up without them, so enclitic forms such as ἐστι are found too; such
+//! analyses are marked approximate.
+
+use super::*;
+use index::HcFormIndex;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+const ELISION_MARKS: [char; 5] = ['\u{1FBD}', '\u{1FBF}', '\u{2019}', '\u{02BC}', '\''];
+const ELIDED_VOWELS: [&str; 8] = ["α", "ε", "ι", "ο", "ά", "έ", "ί", "ό"];
+// sentences are split after these; the ano teleia (·) does not end one
+const SENTENCE_END: [char; 4] = ['.', ';', '\u{037E}', '!'];
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcAnalysis {
+    pub form: HcGreekVerbForm,
+    /// 0 if the word is the cell's main form, 1 for its first alternate, ...
+    pub alternate: u8,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcToken {
+    /// the word or punctuation as it is in the text
+    pub text: String,
+    /// the form looked up: for an elided word, the form with the vowel which matched
+    pub normalized: String,
+    pub punctuation: bool,
+    pub space_after: bool,
+    /// every verb form the word could be; empty if it isn't a verb in the lexicon
+    pub analyses: Vec<HcAnalysis>,
+    /// whether the analyses only match without accents
+    pub approximate: bool,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcSentence {
+    pub text: String,
+    pub tokens: Vec<HcToken>,
+}
+
+pub struct HcTagger {
+    verbs: HashMap<u32, Arc<HcGreekVerb>>,
+    index: HcFormIndex,
+    unaccented: HcFormIndex,
+}
+
+impl HcTagger {
+    /// builds both indexes the tagger needs from the lexicon
+    pub fn new(verbs: &[Arc<HcGreekVerb>]) -> io::Result<HcTagger> {
+        HcTagger::with_indexes(
+            verbs,
+            HcFormIndex::build(verbs, false)?,
+            HcFormIndex::build(verbs, true)?,
+        )
+    }
+
+    /// for indexes read from disk; `index` must be accent-sensitive and `unaccented`
+    /// accent-insensitive, an `InvalidInput` error otherwise, and both must have been
+    /// built from `verbs`
+    pub fn with_indexes(
+        verbs: &[Arc<HcGreekVerb>],
+        index: HcFormIndex,
+        unaccented: HcFormIndex,
+    ) -> io::Result<HcTagger> {
+        if index.is_accent_insensitive() || !unaccented.is_accent_insensitive() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the tagger needs an accent-sensitive index and an accent-insensitive one, \
+                 in that order",
+            ));
+        }
+        Ok(HcTagger {
+            verbs: verbs.iter().map(|v| (v.id, v.clone())).collect(),
+            index,
+            unaccented,
+        })
+    }
+
+    pub fn tag(&self, text: &str) -> Vec<HcSentence> {
+        let mut sentences = Vec::new();
+        let mut tokens = Vec::new();
+        let mut start = None;
+        for (pos, token, space_after) in tokenize(text) {
+            start.get_or_insert(pos);
+            let end_of_sentence =
+                token.chars().count() == 1 && token.chars().all(|c| SENTENCE_END.contains(&c));
+            tokens.push(self.tag_token(token, space_after));
+            if end_of_sentence {
+                let begin = start.take().unwrap();
+                sentences.push(HcSentence {
+                    text: text[begin..pos + token.len()].to_string(),
+                    tokens: std::mem::take(&mut tokens),
+                });
+            }
+        }
+        if let Some(begin) = start {
+            sentences.push(HcSentence {
+                text: text[begin..].trim_end().to_string(),
+                tokens,
+            });
+        }
+        sentences
+    }
+
+    fn tag_token(&self, text: &str, space_after: bool) -> HcToken {
+        let mut token = HcToken {
+            text: text.to_string(),
+            normalized: text.to_string(),
+            punctuation: !text.chars().any(char::is_alphabetic),
+            space_after,
+            analyses: Vec::new(),
+            approximate: false,
+        };
+        if token.punctuation {
+            return token;
+        }
+
+        let candidates = lookup_candidates(text);
+        for (index, approximate) in [(&self.index, false), (&self.unaccented, true)] {
+            for candidate in &candidates {
+                let entries = index.lookup(candidate);
+                if entries.is_empty() {
+                    continue;
+                }
+                if token.analyses.is_empty() {
+                    token.normalized = candidate.clone();
+                    token.approximate = approximate;
+                }
+                for e in entries {
+                    let Some(form) = self
+                        .verbs
+                        .get(&e.verb_id)
+                        .and_then(|verb| e.parameters.to_form(verb))
+                    else {
+                        continue;
+                    };
+                    if !token.analyses.iter().any(|a| a.form == form) {
+                        token.analyses.push(HcAnalysis {
+                            form,
+                            alternate: e.alternate,
+                        });
+                    }
+                }
+            }
+            if !token.analyses.is_empty() {
+                break;
+            }
+        }
+        if token.analyses.is_empty() {
+            token.normalized = candidates[0].clone();
+        }
+        token
+    }
+}
+
+fn is_elision_mark(c: char) -> bool {
+    ELISION_MARKS.contains(&c)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphabetic() || ('\u{0300}'..='\u{036F}').contains(&c)
+}
+
+/// splits text into words and punctuation, with their byte offsets and whether a space
+/// follows; an elision mark stays with its word
+fn tokenize(text: &str) -> Vec<(usize, &str, bool)> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        if is_word_char(c) {
+            while let Some(&(pos, next)) = chars.peek() {
+                if is_word_char(next) {
+                    end = pos + next.len_utf8();
+                    chars.next();
+                } else if is_elision_mark(next) {
+                    end = pos + next.len_utf8();
+                    chars.next();
+                    break;
+                } else {
+                    break;
+                }
+            }
+        }
+        let space_after = text[end..].starts_with(char::is_whitespace) || end == text.len();
+        tokens.push((start, &text[start..end], space_after));
+    }
+    tokens
+}
+
+// oxia to tonos, as generated forms use tonos; grave to acute; a second accent from an
+// enclitic dropped
+fn normalize_accents(word: &str) -> String {
+    let word = input::oxia(word);
+    let mut accents = 0;
+    let letters: Vec<HGKLetter> = word
+        .gkletters()
+        .map(|mut l| {
+            if l.diacritics & HGK_GRAVE != 0 {
+                l.toggle_diacritic(HGK_ACUTE, true);
+            }
+            if l.diacritics & (HGK_ACUTE | HGK_CIRCUMFLEX) != 0 {
+                accents += 1;
+                if accents > 1 {
+                    l.toggle_diacritic(HGK_ACUTE, false);
+                }
+            }
+            l
+        })
+        .collect();
+    letters
+        .iter()
+        .map(|l| l.to_string(HgkUnicodeMode::Precomposed))
+        .collect()
+}
+
+// καὶ ἐπαίδευσε > κἀπαίδευσε: the coronis is written as a smooth breathing, and καί's
+// α absorbs an ε or α
+fn undo_crasis(word: &str) -> Vec<String> {
+    let letters: Vec<HGKLetter> = word.gkletters().collect();
+    if letters.len() < 3
+        || !matches!(letters[0].letter, 'κ' | 'χ')
+        || letters[1].diacritics & HGK_SMOOTH == 0
+    {
+        return Vec::new();
+    }
+    let rest: String = letters[2..]
+        .iter()
+        .map(|l| l.to_string(HgkUnicodeMode::Precomposed))
+        .collect();
+    let vowels: &[char] = if letters[1].letter == 'α' {
+        &['ε', 'α']
+    } else {
+        &[letters[1].letter]
+    };
+    vowels
+        .iter()
+        .map(|v| {
+            let mut first = letters[1];
+            first.letter = *v;
+            if letters[0].letter == 'χ' {
+                // καί before a rough breathing
+                first.toggle_diacritic(HGK_SMOOTH, false);
+                first.toggle_diacritic(HGK_ROUGH, true);
+            }
+            format!("{}{}", first.to_string(HgkUnicodeMode::Precomposed), rest)
+        })
+        .collect()
+}
+
+/// the forms a word in a text could stand for, most likely first
+pub(crate) fn lookup_candidates(word: &str) -> Vec<String> {
+    let mut candidates = Vec::new();
+    if let Some(stem) = word.strip_suffix(is_elision_mark) {
+        let stem = normalize_accents(stem);
+        let accented = hgk_has_diacritics(&stem, HGK_ACUTE | HGK_CIRCUMFLEX);
+        for vowel in ELIDED_VOWELS {
+            // an accent on the elided vowel is only possible if the rest has none
+            if accented && vowel != hgk_strip_diacritics(vowel, HGK_ACUTE) {
+                continue;
+            }
+            candidates.push(format!("{}{}", stem, vowel));
+        }
+    } else {
+        candidates.push(normalize_accents(word));
+    }
+    let crasis: Vec<String> = candidates.iter().flat_map(|c| undo_crasis(c)).collect();
+    candidates.extend(crasis);
+    candidates
+}
+
+/// the Universal Dependencies features of a form, e.g.
+/// Aspect=Perf|Mood=Ind|Number=Plur|Person=3|Tense=Past|VerbForm=Fin|Voice=Act
+pub fn ud_features(vf: &HcGreekVerbForm) -> String {
+    let (tense, aspect) = match vf.tense {
+        HcTense::Present => ("Pres", Some("Imp")),
+        HcTense::Imperfect => ("Past", Some("Imp")),
+        HcTense::Future => ("Fut", None),
+        HcTense::Aorist => ("Past", Some("Perf")),
+        HcTense::Perfect => ("Pres", Some("Perf")),
+        HcTense::Pluperfect => ("Pqp", Some("Perf")),
+    };
+    let (mood, verb_form) = match vf.mood {
+        HcMood::Indicative => (Some("Ind"), "Fin"),
+        HcMood::Subjunctive => (Some("Sub"), "Fin"),
+        HcMood::Optative => (Some("Opt"), "Fin"),
+        HcMood::Imperative => (Some("Imp"), "Fin"),
+        HcMood::Infinitive => (None, "Inf"),
+        HcMood::Participle => (None, "Part"),
+    };
+    let voice = match vf.voice {
+        HcVoice::Active => "Act",
+        HcVoice::Middle => "Mid",
+        HcVoice::Passive => "Pass",
+    };
+    let number = vf.number.map(|n| match n {
+        HcNumber::Singular => "Sing",
+        HcNumber::Dual => "Dual",
+        HcNumber::Plural => "Plur",
+    });
+    let gender = vf.gender.map(|g| match g {
+        HcGender::Masculine => "Masc",
+        HcGender::Feminine => "Fem",
+        HcGender::Neuter => "Neut",
+    });
+    let case = vf.case.map(|c| match c {
+        HcCase::Nominative => "Nom",
+        HcCase::Genitive => "Gen",
+        HcCase::Dative => "Dat",
+        HcCase::Accusative => "Acc",
+        HcCase::Vocative => "Voc",
+    });
+    let person = vf.person.as_ref().map(|p| p.value());
+
+    // in alphabetical order, as CoNLL-U requires
+    [
+        ("Aspect", aspect),
+        ("Case", case),
+        ("Gender", gender),
+        ("Mood", mood),
+        ("Number", number),
+        ("Person", person),
+        ("Tense", Some(tense)),
+        ("VerbForm", Some(verb_form)),
+        ("Voice", Some(voice)),
+    ]
+    .iter()
+    .filter_map(|(feature, value)| value.map(|v| format!("{}={}", feature, v)))
+    .collect::<Vec<_>>()
+    .join("|")
+}
+
+/// CoNLL-U, one token per line with its first analysis; the other analyses of an
+/// ambiguous token are listed in an `# analyses <id> = ` comment before the sentence
+pub fn to_conllu(sentences: &[HcSentence]) -> String {
+    let mut out = String::new();
+    for (n, sentence) in sentences.iter().enumerate() {
+        writeln!(out, "# sent_id = {}", n + 1).unwrap();
+        writeln!(out, "# text = {}", sentence.text).unwrap();
+        for (id, token) in sentence.tokens.iter().enumerate() {
+            if token.analyses.len() > 1 {
+                let analyses: Vec<String> = token
+                    .analyses
+                    .iter()
+                    .map(|a| format!("{} {}", a.form.verb.get_verb_lemma(), ud_features(&a.form)))
+                    .collect();
+                writeln!(out, "# analyses {} = {}", id + 1, analyses.join(" ; ")).unwrap();
+            }
+        }
+
+        for (id, token) in sentence.tokens.iter().enumerate() {
+            let (lemma, upos, feats) = match token.analyses.first() {
+                Some(a) => (a.form.verb.get_verb_lemma(), "VERB", ud_features(&a.form)),
+                None if token.punctuation => (token.text.clone(), "PUNCT", String::from("_")),
+                None => (String::from("_"), "_", String::from("_")),
+            };
+            let mut misc = Vec::new();
+            if token.normalized != token.text && !token.analyses.is_empty() {
+                misc.push(format!("Norm={}", token.normalized));
+            }
+            if token.approximate {
+                misc.push(String::from("Approximate=Yes"));
+            }
+            if !token.space_after {
+                misc.push(String::from("SpaceAfter=No"));
+            }
+            let misc = if misc.is_empty() {
+                String::from("_")
+            } else {
+                misc.join("|")
+            };
+            writeln!(
+                out,
+                "{}\t{}\t{}\t{}\t_\t{}\t_\t_\t_\t{}",
+                id + 1,
+                token.text,
+                lemma,
+                upos,
+                feats,
+                misc
+            )
+            .unwrap();
+        }
+        out.push('\n');
+    }
+    out
+}
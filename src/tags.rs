@@ -0,0 +1,383 @@
+//! Conversion between forms and the morphology tags of treebanks: the 9-character postags
+//! of the Ancient Greek Dependency Treebank (AGDT), e.g. `v3saia---`, and the 10-character
+//! morphology field of PROIEL, e.g. `3saia----i`, whose part of speech (`V-`) is a column
+//! of its own.
+//!
+//! A tag for something this crate can't represent, such as the future perfect or PROIEL's
+//! middle-or-passive voice, is an `Unsupported` error rather than the nearest form.
+
+use super::*;
+use std::fmt;
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum HcTagError {
+    /// the tag is not 9 (AGDT) or 10 (PROIEL) characters long
+    Length(usize),
+    /// the part of speech is not a verb
+    NotAVerb(char),
+    /// not a value the scheme has for this field
+    Invalid { field: &'static str, value: char },
+    /// a value of the scheme which has no equivalent here
+    Unsupported { field: &'static str, value: char },
+    /// a field the form needs, e.g. the person of a finite verb, is blank
+    Missing(&'static str),
+    /// a field the form can't have, e.g. the case of a finite verb, is filled
+    Unexpected { field: &'static str, value: char },
+}
+
+impl fmt::Display for HcTagError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HcTagError::Length(len) => write!(f, "tag has {} characters", len),
+            HcTagError::NotAVerb(pos) => write!(f, "part of speech '{}' is not a verb", pos),
+            HcTagError::Invalid { field, value } => write!(f, "invalid {} '{}'", field, value),
+            HcTagError::Unsupported { field, value } => {
+                write!(f, "{} '{}' is not supported", field, value)
+            }
+            HcTagError::Missing(field) => write!(f, "{} is missing", field),
+            HcTagError::Unexpected { field, value } => {
+                write!(f, "{} '{}' is not expected for this mood", field, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HcTagError {}
+
+// the letters the two schemes share
+fn person_letter(vf: &HcGreekVerbForm) -> char {
+    match vf.person {
+        Some(HcPerson::First) => '1',
+        Some(HcPerson::Second) => '2',
+        Some(HcPerson::Third) => '3',
+        None => '-',
+    }
+}
+
+fn number_letter(vf: &HcGreekVerbForm) -> char {
+    match vf.number {
+        Some(HcNumber::Singular) => 's',
+        Some(HcNumber::Dual) => 'd',
+        Some(HcNumber::Plural) => 'p',
+        None => '-',
+    }
+}
+
+fn tense_letter(vf: &HcGreekVerbForm) -> char {
+    match vf.tense {
+        HcTense::Present => 'p',
+        HcTense::Imperfect => 'i',
+        HcTense::Future => 'f',
+        HcTense::Aorist => 'a',
+        HcTense::Perfect => 'r',
+        HcTense::Pluperfect => 'l',
+    }
+}
+
+fn mood_letter(vf: &HcGreekVerbForm) -> char {
+    match vf.mood {
+        HcMood::Indicative => 'i',
+        HcMood::Subjunctive => 's',
+        HcMood::Optative => 'o',
+        HcMood::Imperative => 'm',
+        HcMood::Infinitive => 'n',
+        HcMood::Participle => 'p',
+    }
+}
+
+fn voice_letter(vf: &HcGreekVerbForm) -> char {
+    match vf.voice {
+        HcVoice::Active => 'a',
+        HcVoice::Middle => 'm',
+        HcVoice::Passive => 'p',
+    }
+}
+
+fn gender_letter(vf: &HcGreekVerbForm) -> char {
+    match vf.gender {
+        Some(HcGender::Masculine) => 'm',
+        Some(HcGender::Feminine) => 'f',
+        Some(HcGender::Neuter) => 'n',
+        None => '-',
+    }
+}
+
+fn case_letter(vf: &HcGreekVerbForm) -> char {
+    match vf.case {
+        Some(HcCase::Nominative) => 'n',
+        Some(HcCase::Genitive) => 'g',
+        Some(HcCase::Dative) => 'd',
+        Some(HcCase::Accusative) => 'a',
+        Some(HcCase::Vocative) => 'v',
+        None => '-',
+    }
+}
+
+fn parse_person(c: char, unsupported: &str) -> Result<Option<HcPerson>, HcTagError> {
+    match c {
+        '1' => Ok(Some(HcPerson::First)),
+        '2' => Ok(Some(HcPerson::Second)),
+        '3' => Ok(Some(HcPerson::Third)),
+        '-' => Ok(None),
+        c if unsupported.contains(c) => Err(HcTagError::Unsupported {
+            field: "person",
+            value: c,
+        }),
+        _ => Err(HcTagError::Invalid {
+            field: "person",
+            value: c,
+        }),
+    }
+}
+
+fn parse_number(c: char, unsupported: &str) -> Result<Option<HcNumber>, HcTagError> {
+    match c {
+        's' => Ok(Some(HcNumber::Singular)),
+        'd' => Ok(Some(HcNumber::Dual)),
+        'p' => Ok(Some(HcNumber::Plural)),
+        '-' => Ok(None),
+        c if unsupported.contains(c) => Err(HcTagError::Unsupported {
+            field: "number",
+            value: c,
+        }),
+        _ => Err(HcTagError::Invalid {
+            field: "number",
+            value: c,
+        }),
+    }
+}
+
+fn parse_tense(c: char, unsupported: &str) -> Result<HcTense, HcTagError> {
+    match c {
+        'p' => Ok(HcTense::Present),
+        'i' => Ok(HcTense::Imperfect),
+        'f' => Ok(HcTense::Future),
+        'a' => Ok(HcTense::Aorist),
+        'r' => Ok(HcTense::Perfect),
+        'l' => Ok(HcTense::Pluperfect),
+        '-' => Err(HcTagError::Missing("tense")),
+        c if unsupported.contains(c) => Err(HcTagError::Unsupported {
+            field: "tense",
+            value: c,
+        }),
+        _ => Err(HcTagError::Invalid {
+            field: "tense",
+            value: c,
+        }),
+    }
+}
+
+fn parse_mood(c: char, unsupported: &str) -> Result<HcMood, HcTagError> {
+    match c {
+        'i' => Ok(HcMood::Indicative),
+        's' => Ok(HcMood::Subjunctive),
+        'o' => Ok(HcMood::Optative),
+        'm' => Ok(HcMood::Imperative),
+        'n' => Ok(HcMood::Infinitive),
+        'p' => Ok(HcMood::Participle),
+        '-' => Err(HcTagError::Missing("mood")),
+        c if unsupported.contains(c) => Err(HcTagError::Unsupported {
+            field: "mood",
+            value: c,
+        }),
+        _ => Err(HcTagError::Invalid {
+            field: "mood",
+            value: c,
+        }),
+    }
+}
+
+fn parse_voice(c: char, unsupported: &str) -> Result<HcVoice, HcTagError> {
+    match c {
+        'a' => Ok(HcVoice::Active),
+        'm' => Ok(HcVoice::Middle),
+        'p' => Ok(HcVoice::Passive),
+        '-' => Err(HcTagError::Missing("voice")),
+        c if unsupported.contains(c) => Err(HcTagError::Unsupported {
+            field: "voice",
+            value: c,
+        }),
+        _ => Err(HcTagError::Invalid {
+            field: "voice",
+            value: c,
+        }),
+    }
+}
+
+fn parse_gender(c: char, unsupported: &str) -> Result<Option<HcGender>, HcTagError> {
+    match c {
+        'm' => Ok(Some(HcGender::Masculine)),
+        'f' => Ok(Some(HcGender::Feminine)),
+        'n' => Ok(Some(HcGender::Neuter)),
+        '-' => Ok(None),
+        c if unsupported.contains(c) => Err(HcTagError::Unsupported {
+            field: "gender",
+            value: c,
+        }),
+        _ => Err(HcTagError::Invalid {
+            field: "gender",
+            value: c,
+        }),
+    }
+}
+
+fn parse_case(c: char, unsupported: &str) -> Result<Option<HcCase>, HcTagError> {
+    match c {
+        'n' => Ok(Some(HcCase::Nominative)),
+        'g' => Ok(Some(HcCase::Genitive)),
+        'd' => Ok(Some(HcCase::Dative)),
+        'a' => Ok(Some(HcCase::Accusative)),
+        'v' => Ok(Some(HcCase::Vocative)),
+        '-' => Ok(None),
+        c if unsupported.contains(c) => Err(HcTagError::Unsupported {
+            field: "case",
+            value: c,
+        }),
+        _ => Err(HcTagError::Invalid {
+            field: "case",
+            value: c,
+        }),
+    }
+}
+
+fn expect_blank(field: &'static str, value: char) -> Result<(), HcTagError> {
+    if value == '-' {
+        Ok(())
+    } else {
+        Err(HcTagError::Unexpected { field, value })
+    }
+}
+
+/// checks that the person, number, gender and case suit the mood, all being given as
+/// their tag letters; `unsupported` are the scheme's values of each with no equivalent
+fn form_from_fields(
+    verb: &Arc<HcGreekVerb>,
+    letters: [char; 4],
+    tense: HcTense,
+    voice: HcVoice,
+    mood: HcMood,
+    unsupported: [&str; 4],
+) -> Result<HcGreekVerbForm, HcTagError> {
+    let [person, number, gender, case] = letters;
+    let vf = HcGreekVerbForm {
+        verb: verb.clone(),
+        person: parse_person(person, unsupported[0])?,
+        number: parse_number(number, unsupported[1])?,
+        tense,
+        voice,
+        mood,
+        gender: parse_gender(gender, unsupported[2])?,
+        case: parse_case(case, unsupported[3])?,
+    };
+    match mood {
+        HcMood::Infinitive => {
+            expect_blank("person", person)?;
+            expect_blank("number", number)?;
+            expect_blank("gender", gender)?;
+            expect_blank("case", case)?;
+        }
+        HcMood::Participle => {
+            expect_blank("person", person)?;
+            vf.number.ok_or(HcTagError::Missing("number"))?;
+            vf.gender.ok_or(HcTagError::Missing("gender"))?;
+            vf.case.ok_or(HcTagError::Missing("case"))?;
+        }
+        _ => {
+            vf.person.ok_or(HcTagError::Missing("person"))?;
+            vf.number.ok_or(HcTagError::Missing("number"))?;
+            expect_blank("gender", gender)?;
+            expect_blank("case", case)?;
+        }
+    }
+    Ok(vf)
+}
+
+/// the AGDT postag of a form, e.g. `v3saia---`; participles are `v` with mood `p`
+pub fn to_agdt(vf: &HcGreekVerbForm) -> String {
+    [
+        'v',
+        person_letter(vf),
+        number_letter(vf),
+        tense_letter(vf),
+        mood_letter(vf),
+        voice_letter(vf),
+        gender_letter(vf),
+        case_letter(vf),
+        '-',
+    ]
+    .iter()
+    .collect()
+}
+
+/// the form of `verb` an AGDT postag describes; the part of speech may be `v` or, as in
+/// older releases for participles, `t`
+pub fn from_agdt(verb: &Arc<HcGreekVerb>, tag: &str) -> Result<HcGreekVerbForm, HcTagError> {
+    let c: Vec<char> = tag.chars().collect();
+    if c.len() != 9 {
+        return Err(HcTagError::Length(c.len()));
+    }
+    if c[0] != 'v' && c[0] != 't' {
+        return Err(HcTagError::NotAVerb(c[0]));
+    }
+    // future perfect; medio-passive; gerundive; locative
+    let tense = parse_tense(c[3], "t")?;
+    let mood = parse_mood(c[4], "g")?;
+    let voice = parse_voice(c[5], "e")?;
+    expect_blank("degree", c[8])?;
+    form_from_fields(
+        verb,
+        [c[1], c[2], c[6], c[7]],
+        tense,
+        voice,
+        mood,
+        ["", "", "", "l"],
+    )
+}
+
+/// the PROIEL morphology field of a form, e.g. `3saia----i`
+pub fn to_proiel(vf: &HcGreekVerbForm) -> String {
+    [
+        person_letter(vf),
+        number_letter(vf),
+        tense_letter(vf),
+        mood_letter(vf),
+        voice_letter(vf),
+        gender_letter(vf),
+        case_letter(vf),
+        '-',
+        '-',
+        'i',
+    ]
+    .iter()
+    .collect()
+}
+
+/// the form of `verb` a PROIEL morphology field describes; the part of speech is not
+/// part of the field, and should be checked to be `V-` first
+pub fn from_proiel(verb: &Arc<HcGreekVerb>, tag: &str) -> Result<HcGreekVerbForm, HcTagError> {
+    let c: Vec<char> = tag.chars().collect();
+    if c.len() != 10 {
+        return Err(HcTagError::Length(c.len()));
+    }
+    // resultative, future perfect, past, uncertain; gerund, gerundive, supine; middle or
+    // passive; the combined genders; the combined and other cases
+    let tense = parse_tense(c[2], "stux")?;
+    let mood = parse_mood(c[3], "dgux")?;
+    let voice = parse_voice(c[4], "ex")?;
+    expect_blank("degree", c[7])?;
+    expect_blank("strength", c[8])?;
+    if c[9] != 'i' {
+        return Err(HcTagError::Invalid {
+            field: "inflection",
+            value: c[9],
+        });
+    }
+    form_from_fields(
+        verb,
+        [c[0], c[1], c[5], c[6]],
+        tense,
+        voice,
+        mood,
+        ["x", "x", "opqrx", "bceilowxz"],
+    )
+}
@@ -0,0 +1,300 @@
+//! Checking generated forms against the verbs attested in treebank XML: AGDT (`<word>`
+//! elements with `form`, `lemma` and `postag`) and PROIEL (`<token>` elements with
+//! `form`, `lemma`, `part-of-speech` and `morphology`).
+//!
+//! Each verb token whose lemma is in the lexicon is generated from its tag and compared
+//! with the attested form, ignoring vowel length, case and the accent changes of running
+//! text (grave for acute, enclitic accents, elision, crasis; see `tagger`).  A lemma shared
+//! by several verbs of the lexicon is checked against each, and the medio-passive voice as
+//! both the middle and the passive.
+
+use super::*;
+use index::normalize_key;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::io::BufRead;
+use tags::HcTagError;
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum HcTreebankFormat {
+    Agdt,
+    Proiel,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcTreebankToken {
+    pub format: HcTreebankFormat,
+    pub sentence_id: String,
+    pub token_id: String,
+    pub form: String,
+    pub lemma: String,
+    /// the AGDT postag or PROIEL morphology
+    pub tag: String,
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Result<Option<String>, String> {
+    match e.try_get_attribute(name).map_err(|e| e.to_string())? {
+        Some(a) => Ok(Some(
+            a.unescape_value().map_err(|e| e.to_string())?.into_owned(),
+        )),
+        None => Ok(None),
+    }
+}
+
+/// the verb tokens of an AGDT or PROIEL file, the format being told by the element names
+pub fn read_verb_tokens<R: BufRead>(r: R) -> Result<Vec<HcTreebankToken>, String> {
+    let mut reader = Reader::from_reader(r);
+    let mut buf = Vec::new();
+    let mut tokens = Vec::new();
+    let mut sentence_id = String::new();
+    loop {
+        let e = match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) => break,
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => e,
+            Ok(_) => {
+                buf.clear();
+                continue;
+            }
+            Err(e) => return Err(format!("at byte {}: {}", reader.buffer_position(), e)),
+        };
+        let token = match e.name().as_ref() {
+            b"sentence" => {
+                sentence_id = attribute(&e, "id")?.unwrap_or_default();
+                None
+            }
+            b"word" => match attribute(&e, "postag")? {
+                Some(tag) if tag.starts_with('v') || tag.starts_with('t') => {
+                    Some((HcTreebankFormat::Agdt, tag))
+                }
+                _ => None,
+            },
+            b"token" => match (
+                attribute(&e, "part-of-speech")?,
+                attribute(&e, "morphology")?,
+            ) {
+                (Some(pos), Some(tag)) if pos == "V-" => Some((HcTreebankFormat::Proiel, tag)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some((format, tag)) = token {
+            if let (Some(form), Some(lemma)) = (attribute(&e, "form")?, attribute(&e, "lemma")?) {
+                tokens.push(HcTreebankToken {
+                    format,
+                    sentence_id: sentence_id.clone(),
+                    token_id: attribute(&e, "id")?.unwrap_or_default(),
+                    form,
+                    lemma,
+                    tag,
+                });
+            }
+        }
+        buf.clear();
+    }
+    Ok(tokens)
+}
+
+/// how a generated form differs from the attested one
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum HcMismatch {
+    /// only in accents or breathings
+    Accent,
+    /// only in the first letters
+    Augment,
+    /// only in the last letters
+    Ending,
+    Stem,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum HcCoverageResult {
+    Match,
+    Mismatch {
+        generated: String,
+        mismatch: HcMismatch,
+    },
+    /// get_form refused the cell
+    Refused(HcFormError),
+    /// the tag has no equivalent form
+    Unsupported(HcTagError),
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcCoverageEntry {
+    pub token: HcTreebankToken,
+    pub result: HcCoverageResult,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct HcCoverageReport {
+    pub entries: Vec<HcCoverageEntry>,
+    /// verb tokens whose lemma is not in the lexicon
+    pub other_lemmas: usize,
+}
+
+impl HcCoverageReport {
+    pub fn matches(&self) -> usize {
+        self.count(|r| *r == HcCoverageResult::Match)
+    }
+
+    pub fn mismatches(&self) -> usize {
+        self.count(|r| matches!(r, HcCoverageResult::Mismatch { .. }))
+    }
+
+    pub fn mismatches_of(&self, kind: HcMismatch) -> usize {
+        self.count(
+            |r| matches!(r, HcCoverageResult::Mismatch { mismatch, .. } if *mismatch == kind),
+        )
+    }
+
+    pub fn refused(&self) -> usize {
+        self.count(|r| matches!(r, HcCoverageResult::Refused(_)))
+    }
+
+    pub fn unsupported(&self) -> usize {
+        self.count(|r| matches!(r, HcCoverageResult::Unsupported(_)))
+    }
+
+    /// matches out of the tokens with a supported tag
+    pub fn accuracy(&self) -> f64 {
+        let checked = self.entries.len() - self.unsupported();
+        if checked == 0 {
+            0.0
+        } else {
+            self.matches() as f64 / checked as f64
+        }
+    }
+
+    fn count(&self, f: impl Fn(&HcCoverageResult) -> bool) -> usize {
+        self.entries.iter().filter(|e| f(&e.result)).count()
+    }
+}
+
+// treebank lemmas number homographs (AGDT's πείθω1) and don't mark vowel length
+fn lemma_key(lemma: &str) -> String {
+    normalize_key(lemma.trim_end_matches(|c: char| c.is_ascii_digit()), false)
+}
+
+// the tags to generate the token from: the medio-passive voice `e` of both schemes as
+// the middle and as the passive
+fn voice_readings(token: &HcTreebankToken) -> Vec<String> {
+    let voice = match token.format {
+        HcTreebankFormat::Agdt => 5,
+        HcTreebankFormat::Proiel => 4,
+    };
+    if token.tag.chars().nth(voice) != Some('e') {
+        return vec![token.tag.clone()];
+    }
+    ['m', 'p']
+        .iter()
+        .map(|v| {
+            token
+                .tag
+                .chars()
+                .enumerate()
+                .map(|(i, c)| if i == voice { *v } else { c })
+                .collect()
+        })
+        .collect()
+}
+
+fn check_token(verb: &Arc<HcGreekVerb>, token: &HcTreebankToken, tag: &str) -> HcCoverageResult {
+    let vf = match token.format {
+        HcTreebankFormat::Agdt => tags::from_agdt(verb, tag),
+        HcTreebankFormat::Proiel => tags::from_proiel(verb, tag),
+    };
+    match vf {
+        Err(e) => HcCoverageResult::Unsupported(e),
+        Ok(vf) => match vf.get_form_alternates(false) {
+            Err(e) => HcCoverageResult::Refused(e),
+            Ok(alternates) => compare_form(&token.form, &alternates),
+        },
+    }
+}
+
+// a match first, then a mismatch, a refusal and an unsupported tag
+fn rank(result: &HcCoverageResult) -> u8 {
+    match result {
+        HcCoverageResult::Match => 0,
+        HcCoverageResult::Mismatch { .. } => 1,
+        HcCoverageResult::Refused(_) => 2,
+        HcCoverageResult::Unsupported(_) => 3,
+    }
+}
+
+/// checks each token whose lemma is in the lexicon, against every verb with that lemma
+/// and, for a medio-passive tag, as both middle and passive; the best result is kept
+pub fn coverage(verbs: &[Arc<HcGreekVerb>], tokens: &[HcTreebankToken]) -> HcCoverageReport {
+    let mut lemmas: HashMap<String, Vec<&Arc<HcGreekVerb>>> = HashMap::new();
+    for verb in verbs {
+        lemmas
+            .entry(lemma_key(&verb.get_verb_lemma()))
+            .or_default()
+            .push(verb);
+    }
+
+    let mut report = HcCoverageReport::default();
+    for token in tokens {
+        let Some(candidates) = lemmas.get(&lemma_key(&token.lemma)) else {
+            report.other_lemmas += 1;
+            continue;
+        };
+        let readings = voice_readings(token);
+        let result = candidates
+            .iter()
+            .flat_map(|verb| readings.iter().map(|tag| check_token(verb, token, tag)))
+            .min_by_key(rank)
+            .unwrap();
+        report.entries.push(HcCoverageEntry {
+            token: token.clone(),
+            result,
+        });
+    }
+    report
+}
+
+fn compare_form(attested: &str, generated: &[HcAlternate]) -> HcCoverageResult {
+    let candidates: Vec<String> = tagger::lookup_candidates(attested)
+        .iter()
+        .map(|c| normalize_key(c, false))
+        .collect();
+    let alternates: Vec<String> = generated.iter().flat_map(|a| a.spellings()).collect();
+    if alternates
+        .iter()
+        .any(|a| candidates.contains(&normalize_key(a, false)))
+    {
+        return HcCoverageResult::Match;
+    }
+
+    // against the main form, as attested in the text
+    let attested = normalize_key(&candidates[0], true);
+    let main = normalize_key(&alternates[0], true);
+    let mismatch = if alternates
+        .iter()
+        .any(|a| normalize_key(a, true) == attested)
+    {
+        HcMismatch::Accent
+    } else {
+        let a: Vec<char> = attested.chars().collect();
+        let b: Vec<char> = main.chars().collect();
+        let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+        let suffix = a
+            .iter()
+            .rev()
+            .zip(b.iter().rev())
+            .take_while(|(x, y)| x == y)
+            .count();
+        if prefix >= a.len().min(b.len()).saturating_sub(3) {
+            HcMismatch::Ending
+        } else if suffix >= a.len().min(b.len()).saturating_sub(2) {
+            HcMismatch::Augment
+        } else {
+            HcMismatch::Stem
+        }
+    };
+    HcCoverageResult::Mismatch {
+        generated: join_alternates(generated),
+        mismatch,
+    }
+}
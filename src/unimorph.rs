@@ -0,0 +1,300 @@
+//! UniMorph verb tables: lines of lemma, form and features separated by tabs, the
+//! features being e.g. `V;IND;PST;PFV;ACT;1;SG`.
+//!
+//! Tense is given as tense and aspect in the indicative and by aspect alone in the other
+//! moods, except for the future: present IPFV, imperfect PST;IPFV, future FUT, aorist PFV
+//! (PST;PFV), perfect PRF (PRS;PRF), pluperfect PST;PRF.  Infinitives are `V;NFIN`,
+//! participles `V.PTCP`.  Each alternate of a form is a row of its own, and so are the
+//! forms with and without a movable ν.
+
+use super::*;
+use parameters::HcParameterSet;
+use std::io::{self, BufRead, Write};
+
+/// the UniMorph features of a form
+pub fn unimorph_features(vf: &HcGreekVerbForm) -> String {
+    let mut features = match vf.mood {
+        HcMood::Indicative => vec!["V", "IND"],
+        HcMood::Subjunctive => vec!["V", "SBJV"],
+        HcMood::Optative => vec!["V", "OPT"],
+        HcMood::Imperative => vec!["V", "IMP"],
+        HcMood::Infinitive => vec!["V", "NFIN"],
+        HcMood::Participle => vec!["V.PTCP"],
+    };
+    let indicative = vf.mood == HcMood::Indicative;
+    match vf.tense {
+        HcTense::Present if indicative => features.extend(["PRS", "IPFV"]),
+        HcTense::Present => features.push("IPFV"),
+        HcTense::Imperfect => features.extend(["PST", "IPFV"]),
+        HcTense::Future => features.push("FUT"),
+        HcTense::Aorist if indicative => features.extend(["PST", "PFV"]),
+        HcTense::Aorist => features.push("PFV"),
+        HcTense::Perfect if indicative => features.extend(["PRS", "PRF"]),
+        HcTense::Perfect => features.push("PRF"),
+        HcTense::Pluperfect => features.extend(["PST", "PRF"]),
+    }
+    features.push(match vf.voice {
+        HcVoice::Active => "ACT",
+        HcVoice::Middle => "MID",
+        HcVoice::Passive => "PASS",
+    });
+    if let Some(person) = vf.person {
+        features.push(match person {
+            HcPerson::First => "1",
+            HcPerson::Second => "2",
+            HcPerson::Third => "3",
+        });
+    }
+    if let Some(gender) = vf.gender {
+        features.push(match gender {
+            HcGender::Masculine => "MASC",
+            HcGender::Feminine => "FEM",
+            HcGender::Neuter => "NEUT",
+        });
+    }
+    if let Some(case) = vf.case {
+        features.push(match case {
+            HcCase::Nominative => "NOM",
+            HcCase::Genitive => "GEN",
+            HcCase::Dative => "DAT",
+            HcCase::Accusative => "ACC",
+            HcCase::Vocative => "VOC",
+        });
+    }
+    if let Some(number) = vf.number {
+        features.push(match number {
+            HcNumber::Singular => "SG",
+            HcNumber::Dual => "DU",
+            HcNumber::Plural => "PL",
+        });
+    }
+    features.join(";")
+}
+
+/// the parameters of a verb's UniMorph features, in any order; features with no
+/// equivalent here (e.g. the future perfect, FUT;PRF) are an error, as are other parts
+/// of speech
+pub fn parse_unimorph_features(features: &str) -> Result<HcParameterSet, String> {
+    let mut mood = None;
+    let mut tense = None;
+    let mut aspect = None;
+    let mut set = HcParameterSet::new();
+    let (mut person, mut number, mut gender, mut case) = (false, false, false, false);
+    for feature in features.split(';').map(str::trim) {
+        match feature {
+            "V" => (),
+            "V.PTCP" => mood = Some(HcMood::Participle),
+            "IND" => mood = Some(HcMood::Indicative),
+            "SBJV" => mood = Some(HcMood::Subjunctive),
+            "OPT" => mood = Some(HcMood::Optative),
+            "IMP" => mood = Some(HcMood::Imperative),
+            "NFIN" => mood = Some(HcMood::Infinitive),
+            "PRS" | "PST" | "FUT" => tense = Some(feature),
+            "IPFV" | "PFV" | "PRF" => aspect = Some(feature),
+            "ACT" => set = set.with_voice(HcVoice::Active),
+            "MID" => set = set.with_voice(HcVoice::Middle),
+            "PASS" => set = set.with_voice(HcVoice::Passive),
+            "1" | "2" | "3" => {
+                person = true;
+                set = set.with_person(match feature {
+                    "1" => HcPerson::First,
+                    "2" => HcPerson::Second,
+                    _ => HcPerson::Third,
+                });
+            }
+            "SG" | "DU" | "PL" => {
+                number = true;
+                set = set.with_number(match feature {
+                    "SG" => HcNumber::Singular,
+                    "DU" => HcNumber::Dual,
+                    _ => HcNumber::Plural,
+                });
+            }
+            "MASC" | "FEM" | "NEUT" => {
+                gender = true;
+                set = set.with_gender(match feature {
+                    "MASC" => HcGender::Masculine,
+                    "FEM" => HcGender::Feminine,
+                    _ => HcGender::Neuter,
+                });
+            }
+            "NOM" | "GEN" | "DAT" | "ACC" | "VOC" => {
+                case = true;
+                set = set.with_case(match feature {
+                    "NOM" => HcCase::Nominative,
+                    "GEN" => HcCase::Genitive,
+                    "DAT" => HcCase::Dative,
+                    "ACC" => HcCase::Accusative,
+                    _ => HcCase::Vocative,
+                });
+            }
+            f if features.starts_with("V;") || features.starts_with("V.PTCP") => {
+                return Err(format!("unsupported feature: {}", f))
+            }
+            _ => return Err(format!("not a verb: {}", features)),
+        }
+    }
+
+    let mood = mood.ok_or("no mood")?;
+    let indicative = mood == HcMood::Indicative;
+    let tense = match (indicative, tense, aspect) {
+        (_, Some("FUT"), None) => HcTense::Future,
+        (true, Some("PRS"), Some("IPFV") | None) => HcTense::Present,
+        (true, Some("PST"), Some("IPFV")) => HcTense::Imperfect,
+        (true, Some("PST"), Some("PFV")) => HcTense::Aorist,
+        (true, Some("PRS"), Some("PRF")) => HcTense::Perfect,
+        (true, Some("PST"), Some("PRF")) => HcTense::Pluperfect,
+        (false, None | Some("PRS"), Some("IPFV")) => HcTense::Present,
+        (false, None, Some("PFV")) => HcTense::Aorist,
+        (false, None | Some("PRS"), Some("PRF")) => HcTense::Perfect,
+        _ => {
+            return Err(format!(
+                "unsupported tense and aspect: {};{}",
+                tense.unwrap_or("-"),
+                aspect.unwrap_or("-")
+            ))
+        }
+    };
+    if set.voices().len() != 1 {
+        return Err(String::from("no voice"));
+    }
+
+    let complete = match mood {
+        HcMood::Infinitive => !(person || number || gender || case),
+        HcMood::Participle => !person && number && gender && case,
+        _ => person && number && !gender && !case,
+    };
+    if !complete {
+        return Err(format!(
+            "wrong person, number, gender or case for {}",
+            mood.value()
+        ));
+    }
+    Ok(set.with_tense(tense).with_mood(mood))
+}
+
+/// writes every legal form (finite, infinitive and participle) of every verb; returns the
+/// number of rows written
+pub fn export<W: Write>(verbs: &[Arc<HcGreekVerb>], w: &mut W) -> io::Result<usize> {
+    let all = HcParameterSet::new();
+    let forms: Vec<HcGreekVerbForm> = verbs.iter().flat_map(|v| all.forms(v)).collect();
+    let mut rows = 0;
+    for (vf, generated) in forms.iter().zip(generate_form_alternates(&forms, false)) {
+        let Ok(generated) = generated else {
+            continue;
+        };
+        let lemma = vf.verb.get_verb_lemma();
+        let features = unimorph_features(vf);
+        for form in generated.iter().flat_map(|a| a.spellings()) {
+            writeln!(w, "{}\t{}\t{}", lemma, form, features)?;
+            rows += 1;
+        }
+    }
+    Ok(rows)
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcUniMorphRow {
+    /// from 1
+    pub line: usize,
+    pub lemma: String,
+    pub form: String,
+    pub features: String,
+    /// the features' parameters, or why they have none
+    pub parameters: Result<HcParameterSet, String>,
+}
+
+/// reads the rows of a UniMorph file, skipping blank lines; a row's features are parsed
+/// but not checked, so a file with nouns or unsupported features can still be read
+pub fn import<R: BufRead>(r: R) -> io::Result<Vec<HcUniMorphRow>> {
+    let mut rows = Vec::new();
+    for (n, line) in r.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (lemma, form, features) = match fields[..] {
+            [lemma, form, features] => (lemma, form, features),
+            _ => (line.as_str(), "", ""),
+        };
+        rows.push(HcUniMorphRow {
+            line: n + 1,
+            lemma: lemma.to_string(),
+            form: form.to_string(),
+            features: features.to_string(),
+            parameters: if fields.len() == 3 {
+                parse_unimorph_features(features)
+            } else {
+                Err(String::from("not three tab-separated fields"))
+            },
+        });
+    }
+    Ok(rows)
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct HcUniMorphDifference {
+    pub row: HcUniMorphRow,
+    /// what the generator makes of the row's lemma and features
+    pub generated: Result<String, HcFormError>,
+}
+
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct HcUniMorphComparison {
+    pub matching: usize,
+    pub differences: Vec<HcUniMorphDifference>,
+    /// rows whose lemma is not in the lexicon
+    pub unknown_lemmas: Vec<HcUniMorphRow>,
+    /// rows whose features have no parameters here
+    pub unsupported: Vec<HcUniMorphRow>,
+}
+
+// UniMorph data doesn't mark vowel length
+fn without_length(s: &str) -> String {
+    hgk_strip_diacritics(s.trim(), HGK_MACRON | HGK_BREVE)
+}
+
+/// checks each row against the form the generator makes for it; a row matches if its
+/// form is any of the generated alternates.  Lemmas and forms are compared without
+/// macrons or breves.
+pub fn compare(verbs: &[Arc<HcGreekVerb>], rows: &[HcUniMorphRow]) -> HcUniMorphComparison {
+    let mut comparison = HcUniMorphComparison::default();
+    for row in rows {
+        let parameters = match &row.parameters {
+            Ok(parameters) => parameters,
+            Err(_) => {
+                comparison.unsupported.push(row.clone());
+                continue;
+            }
+        };
+        let lemma = without_length(&row.lemma);
+        let Some(verb) = verbs
+            .iter()
+            .find(|v| without_length(&v.get_verb_lemma()) == lemma)
+        else {
+            comparison.unknown_lemmas.push(row.clone());
+            continue;
+        };
+        let Some(vf) = parameters.to_form(verb) else {
+            comparison.unsupported.push(row.clone());
+            continue;
+        };
+        let alternates = vf.get_form_alternates(false);
+        let form = without_length(&row.form);
+        match &alternates {
+            Ok(a)
+                if a.iter()
+                    .flat_map(|a| a.spellings())
+                    .any(|f| without_length(&f) == form) =>
+            {
+                comparison.matching += 1
+            }
+            _ => comparison.differences.push(HcUniMorphDifference {
+                row: row.clone(),
+                generated: alternates.map(|a| join_alternates(&a)),
+            }),
+        }
+    }
+    comparison
+}
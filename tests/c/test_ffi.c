@@ -0,0 +1,96 @@
+/* Exercises the C ABI (src/ffi.rs).  Build and run from this directory with `make`. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "hoplite_verbs.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                   \
+    do {                                                              \
+        if (!(cond)) {                                                \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,    \
+                    __LINE__, #cond);                                 \
+            failures++;                                               \
+        }                                                             \
+    } while (0)
+
+static void check_form(const HcVerb *verb, int16_t person, int16_t number,
+                       int16_t tense, int16_t voice, int16_t mood,
+                       int16_t gender, int16_t kase, bool decompose,
+                       const char *expected)
+{
+    HcFormResult *result = NULL;
+    HcErrorCode code = hc_get_form(verb, person, number, tense, voice, mood,
+                                   gender, kase, decompose, &result);
+    CHECK(code == HC_ERROR_CODE_OK);
+    if (code != HC_ERROR_CODE_OK) {
+        return;
+    }
+    char *form = hc_form_result_form(result);
+    if (strcmp(form, expected) != 0) {
+        fprintf(stderr, "expected %s, got %s\n", expected, form);
+        failures++;
+    }
+    hc_string_free(form);
+    hc_form_result_free(result);
+}
+
+int main(void)
+{
+    HcVerb *paideuo = hc_verb_new(
+        0, "παιδεύω, παιδεύσω, ἐπαίδευσα, πεπαίδευκα, πεπαίδευμαι, ἐπαιδεύθην % 2");
+    HcVerb *pheugo = hc_verb_new(
+        1, "φεύγω, φεύξομαι, ἔφυγον, πέφευγα, —, —");
+    CHECK(paideuo != NULL);
+    CHECK(pheugo != NULL);
+    CHECK(hc_verb_new(2, "παιδεύω, παιδεύσω") == NULL);
+    CHECK(hc_verb_new(2, NULL) == NULL);
+
+    /* 3rd plural aorist middle optative; 1st singular aorist active indicative */
+    check_form(paideuo, 2, 1, 3, 1, 2, -1, -1, false, "παιδεύσαιντο");
+    check_form(paideuo, 0, 0, 3, 0, 0, -1, -1, false, "ἐπαίδευσα");
+    check_form(paideuo, 0, 0, 3, 0, 0, -1, -1, true, "ε ‐ παιδευσ ‐ α");
+    /* aorist passive infinitive: no person or number */
+    check_form(paideuo, -1, -1, 3, 2, 4, -1, -1, false, "παιδευθῆναι");
+    /* present active participle, masculine genitive singular */
+    check_form(paideuo, -1, 0, 0, 0, 5, 0, 1, false, "παιδεύοντος");
+
+    /* the steps, the last being the form */
+    HcFormResult *result = NULL;
+    CHECK(hc_get_form(paideuo, 2, 1, 3, 1, 2, -1, -1, false, &result) == HC_ERROR_CODE_OK);
+    size_t count = hc_form_result_step_count(result);
+    CHECK(count > 1);
+    char *first = hc_form_result_step_form(result, 0);
+    CHECK(strncmp(first, "παιδεύω, παιδεύσω", strlen("παιδεύω, παιδεύσω")) == 0);
+    hc_string_free(first);
+    char *explanation = hc_form_result_step_explanation(result, count - 1);
+    CHECK(strcmp(explanation, "Accent verb") == 0);
+    hc_string_free(explanation);
+    CHECK(hc_form_result_step_form(result, count) == NULL);
+    hc_form_result_free(result);
+
+    /* errors */
+    result = NULL;
+    HcErrorCode code = hc_get_form(pheugo, 0, 0, 4, 2, 0, -1, -1, false, &result);
+    CHECK(code == HC_ERROR_CODE_BLANK_PRINCIPAL_PART);
+    CHECK(result == NULL);
+    CHECK(strcmp(hc_error_name(code), "BlankPrincipalPart") == 0);
+    CHECK(hc_get_form(paideuo, 3, 0, 0, 0, 0, -1, -1, false, &result) == HC_ERROR_CODE_INVALID_ARGUMENT);
+    CHECK(hc_get_form(paideuo, 0, 0, 6, 0, 0, -1, -1, false, &result) == HC_ERROR_CODE_INVALID_ARGUMENT);
+    CHECK(hc_get_form(paideuo, -1, 0, 0, 0, 5, 3, 1, false, &result) == HC_ERROR_CODE_INVALID_ARGUMENT);
+    CHECK(hc_get_form(NULL, 0, 0, 0, 0, 0, -1, -1, false, &result) == HC_ERROR_CODE_INVALID_ARGUMENT);
+    CHECK(hc_get_form(paideuo, 0, 0, 0, 0, 0, -1, -1, false, NULL) == HC_ERROR_CODE_INVALID_ARGUMENT);
+
+    hc_verb_free(paideuo);
+    hc_verb_free(pheugo);
+    hc_verb_free(NULL);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("all checks passed\n");
+    return 0;
+}
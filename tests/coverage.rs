@@ -0,0 +1,167 @@
+#![cfg(feature = "treebank")]
+
+use hoplite_verbs_rs::treebank::*;
+use hoplite_verbs_rs::*;
+use std::fs::File;
+use std::io::BufReader;
+use std::process::Command;
+use std::sync::Arc;
+
+fn tokens(path: &str) -> Vec<HcTreebankToken> {
+    read_verb_tokens(BufReader::new(File::open(path).unwrap())).unwrap()
+}
+
+#[test]
+fn read_treebanks() {
+    let agdt = tokens("testdata/treebank-agdt-sample.xml");
+    // not the article, noun, conjunction or punctuation
+    assert_eq!(agdt.len(), 14);
+    assert_eq!(
+        agdt[0],
+        HcTreebankToken {
+            format: HcTreebankFormat::Agdt,
+            sentence_id: String::from("1"),
+            token_id: String::from("1"),
+            form: String::from("ἐπαίδευσεν"),
+            lemma: String::from("παιδεύω1"),
+            tag: String::from("v3saia---"),
+        }
+    );
+    // entities are unescaped
+    assert_eq!(agdt[9].form, "ἔγραψ\u{2019}");
+
+    let proiel = tokens("testdata/treebank-proiel-sample.xml");
+    // the empty token has no form
+    assert_eq!(proiel.len(), 3);
+    assert_eq!(proiel[0].format, HcTreebankFormat::Proiel);
+    assert_eq!(
+        (proiel[1].sentence_id.as_str(), proiel[1].tag.as_str()),
+        ("100", "3piia----i")
+    );
+
+    assert!(read_verb_tokens("<treebank><sentence id=\"1\"><word".as_bytes()).is_err());
+}
+
+#[test]
+fn coverage_report() {
+    let verbs = read_lexicon(BufReader::new(File::open("testdata/pp.txt").unwrap())).unwrap();
+    let mut all = tokens("testdata/treebank-agdt-sample.xml");
+    all.extend(tokens("testdata/treebank-proiel-sample.xml"));
+    let report = coverage(&verbs, &all);
+
+    assert_eq!(report.other_lemmas, 1);
+    assert_eq!(report.entries.len(), 16);
+    assert_eq!(report.matches(), 11);
+    assert_eq!(report.mismatches(), 3);
+    assert_eq!(report.refused(), 1);
+    assert_eq!(report.unsupported(), 1);
+    assert!((report.accuracy() - 11.0 / 15.0).abs() < 1e-9);
+
+    let result = |form: &str| {
+        &report
+            .entries
+            .iter()
+            .find(|e| e.token.form == form)
+            .unwrap()
+            .result
+    };
+    // movable ν, length, grave accent, elision
+    for form in ["ἐπαίδευσεν", "ἔπεμψε", "λύσας", "ἐστὶ", "ἔγραψ\u{2019}"]
+    {
+        assert_eq!(result(form), &HcCoverageResult::Match, "{}", form);
+    }
+    assert_eq!(
+        result("ἐκελευσε"),
+        &HcCoverageResult::Mismatch {
+            generated: String::from("ἐκέλευσε(ν)"),
+            mismatch: HcMismatch::Accent
+        }
+    );
+    assert_eq!(
+        result("παίδευσα"),
+        &HcCoverageResult::Mismatch {
+            generated: String::from("ἐπαίδευσα"),
+            mismatch: HcMismatch::Augment
+        }
+    );
+    assert_eq!(
+        result("παιδεύουσι"),
+        &HcCoverageResult::Mismatch {
+            generated: String::from("παιδεύωσι(ν)"),
+            mismatch: HcMismatch::Ending
+        }
+    );
+    assert_eq!(
+        result("πέφηκε"),
+        &HcCoverageResult::Refused(HcFormError::BlankPrincipalPartForForm(
+            HcGreekPrincipalParts::Fourth
+        ))
+    );
+    // the medio-passive as the middle or the passive
+    for form in ["παιδεύεσθαι", "παιδεύεται", "ἐπαιδεύθη"] {
+        assert_eq!(result(form), &HcCoverageResult::Match, "{}", form);
+    }
+    assert!(matches!(
+        result("πεπαιδεύσεται"),
+        HcCoverageResult::Unsupported(_)
+    ));
+}
+
+#[test]
+fn coverage_homographs() {
+    // a verb of the same lemma without the aorist, listed first
+    let verbs = [
+        "παιδεύω, —, —, —, —, —",
+        "παιδεύω, παιδεύσω, ἐπαίδευσα, πεπαίδευκα, πεπαίδευμαι, ἐπαιδεύθην",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(idx, pps)| Arc::new(HcGreekVerb::from_string_with_properties(idx as u32, pps).unwrap()))
+    .collect::<Vec<_>>();
+    let agdt = tokens("testdata/treebank-agdt-sample.xml");
+    let report = coverage(&verbs[..1], &agdt[..1]);
+    assert!(matches!(
+        report.entries[0].result,
+        HcCoverageResult::Refused(_)
+    ));
+    let report = coverage(&verbs, &agdt[..1]);
+    assert_eq!(report.entries[0].result, HcCoverageResult::Match);
+}
+
+#[test]
+fn coverage_tool() {
+    let output = Command::new(env!("CARGO_BIN_EXE_hoplite-coverage"))
+        .args([
+            "--summary",
+            "testdata/treebank-agdt-sample.xml",
+            "testdata/treebank-proiel-sample.xml",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "verb tokens: 17, in the lexicon: 16\n\
+         matching: 11\n\
+         mismatched: 3 (accent 1, augment 1, ending 1, stem 0)\n\
+         refused: 1\n\
+         unsupported tags: 1\n\
+         accuracy: 73.33%\n"
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_hoplite-coverage"))
+        .arg("testdata/treebank-agdt-sample.xml")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("2:1\tκελεύω\tv3saia---\tἐκελευσε\tἐκέλευσε(ν) (Accent)\n"));
+    assert!(stdout.contains(
+        "2:7\tφημί\tv3sria---\tπέφηκε\trefused: BlankPrincipalPart (the verb has no fourth \
+         principal part, from which the form is made)\n"
+    ));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_hoplite-coverage"))
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
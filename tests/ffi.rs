@@ -0,0 +1,14 @@
+#![cfg(feature = "ffi")]
+
+// build.rs generates the header into OUT_DIR; the copy in include/ is what C users build
+// against, so it must match
+#[test]
+fn header_is_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/hoplite_verbs.h"));
+    let checked_in = include_str!("../include/hoplite_verbs.h");
+    assert!(
+        generated == checked_in,
+        "include/hoplite_verbs.h is out of date: copy it from {}/hoplite_verbs.h",
+        env!("OUT_DIR")
+    );
+}
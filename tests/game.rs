@@ -0,0 +1,295 @@
+#![cfg(feature = "game")]
+
+use hoplite_verbs_rs::game::*;
+use hoplite_verbs_rs::*;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+
+fn lexicon() -> Vec<Arc<HcGreekVerb>> {
+    read_lexicon(BufReader::new(File::open("testdata/pp.txt").unwrap())).unwrap()
+}
+
+fn form(verb: u32, form: &str) -> HcGameForm {
+    HcGameForm {
+        verb,
+        form: String::from(form),
+    }
+}
+
+fn config() -> HcGameConfig {
+    HcGameConfig {
+        players: vec![String::from("Ann"), String::from("Ben"), String::from("Cy")],
+        lives: 1,
+        changes: 1,
+        answer_time: 30_000,
+        profile: String::from("standard"),
+        start: form(0, "1; sg; pres; act; ind"),
+        started_at: 1_000,
+    }
+}
+
+fn change(player: usize, at: u64, to: &str) -> HcMove {
+    HcMove::Change {
+        player,
+        at,
+        to: form(0, to),
+    }
+}
+
+fn answer(player: usize, at: u64, answer: &str) -> HcMove {
+    HcMove::Answer {
+        player,
+        at,
+        answer: String::from(answer),
+    }
+}
+
+#[test]
+fn play() {
+    let verbs = lexicon();
+    let mut game = HcGame::new(config(), &verbs).unwrap();
+    assert_eq!(game.phase, HcGamePhase::Changing { player: 0 });
+    assert_eq!(game.prompt(&verbs, "<b>", "</b>"), None);
+
+    // Ann changes one parameter for Ben
+    assert_eq!(
+        game.apply(change(0, 2_000, "2; pl; pres; act; ind"), &verbs),
+        Err(HcGameError::WrongChanges {
+            expected: 1,
+            changed: 2
+        })
+    );
+    assert_eq!(
+        game.apply(change(1, 2_000, "2; sg; pres; act; ind"), &verbs),
+        Err(HcGameError::NotYourTurn)
+    );
+    assert_eq!(
+        game.apply(change(0, 2_000, "2; sg; pres; act; ind"), &verbs),
+        Ok(HcGameEvent::Changed { player: 0 })
+    );
+    assert_eq!(
+        game.phase,
+        HcGamePhase::Answering {
+            player: 1,
+            prev: form(0, "1; sg; pres; act; ind"),
+            deadline: 32_000
+        }
+    );
+    assert_eq!(
+        game.prompt(&verbs, "<b>", "</b>").unwrap(),
+        "παιδεύω: <b>Second</b> Singular Present Indicative Active"
+    );
+    assert_eq!(
+        game.apply(answer(0, 3_000, "παιδεύεις"), &verbs),
+        Err(HcGameError::NotYourTurn)
+    );
+    assert_eq!(
+        game.apply(change(0, 3_000, "3; sg; pres; act; ind"), &verbs),
+        Err(HcGameError::WrongPhase)
+    );
+    assert_eq!(
+        game.apply(HcMove::Timeout { at: 3_000 }, &verbs),
+        Err(HcGameError::TooEarly)
+    );
+    assert_eq!(
+        game.apply(answer(1, 500, "παιδεύεις"), &verbs),
+        Err(HcGameError::TimeWentBackwards)
+    );
+    assert_eq!(
+        game.apply(answer(1, 5_000, "παιδεύεις"), &verbs),
+        Ok(HcGameEvent::Answered {
+            player: 1,
+            correct: true,
+            late: false,
+            correct_answer: String::from("παιδεύεις"),
+            lives: 1
+        })
+    );
+    assert_eq!(game.players[1].score, 1);
+
+    // Ben changes the form for Cy, who answers rightly but too late and is out
+    game.apply(change(1, 6_000, "2; pl; pres; act; ind"), &verbs)
+        .unwrap();
+    let event = game.apply(answer(2, 36_001, "παιδεύετε"), &verbs).unwrap();
+    assert!(matches!(
+        event,
+        HcGameEvent::Answered {
+            correct: false,
+            late: true,
+            lives: 0,
+            ..
+        }
+    ));
+    // so Ann changes the form, for Ben
+    assert_eq!(game.phase, HcGamePhase::Changing { player: 0 });
+    game.apply(change(0, 37_000, "2; pl; fut; act; ind"), &verbs)
+        .unwrap();
+    assert!(matches!(
+        game.phase,
+        HcGamePhase::Answering { player: 1, .. }
+    ));
+    let event = game.apply(HcMove::Timeout { at: 67_000 }, &verbs).unwrap();
+    assert!(matches!(
+        event,
+        HcGameEvent::Answered {
+            player: 1,
+            correct: false,
+            ..
+        }
+    ));
+    assert_eq!(game.phase, HcGamePhase::Over { winner: Some(0) });
+    assert_eq!(
+        game.apply(change(0, 68_000, "2; pl; aor; act; ind"), &verbs),
+        Err(HcGameError::GameOver)
+    );
+
+    // the log replays to the same game, and the snapshot restores it
+    assert_eq!(game.log.len(), 6);
+    assert_eq!(HcGame::replay(config(), &game.log, &verbs).unwrap(), game);
+    let snapshot = game.snapshot();
+    assert!(snapshot.contains(r#""move":"Change""#));
+    assert_eq!(HcGame::from_snapshot(&snapshot, &verbs).unwrap(), game);
+    assert!(matches!(
+        HcGame::from_snapshot("{", &verbs),
+        Err(HcGameError::BadSnapshot(_))
+    ));
+
+    // a snapshot is restored only if its log leads to its state
+    let mut tampered = game.clone();
+    tampered.players[0].score += 1;
+    assert!(matches!(
+        HcGame::from_snapshot(&tampered.snapshot(), &verbs),
+        Err(HcGameError::BadSnapshot(_))
+    ));
+    let mut tampered = game.clone();
+    tampered.phase = HcGamePhase::Over { winner: Some(7) };
+    assert!(matches!(
+        HcGame::from_snapshot(&tampered.snapshot(), &verbs),
+        Err(HcGameError::BadSnapshot(_))
+    ));
+    let mut tampered = game.clone();
+    tampered.config.profile = String::from("nonesuch");
+    assert!(matches!(
+        HcGame::from_snapshot(&tampered.snapshot(), &verbs),
+        Err(HcGameError::Config(_))
+    ));
+    let mut tampered = game.clone();
+    tampered.log[1] = answer(5, 3_000, "ἐπαίδευσεν");
+    assert_eq!(
+        HcGame::from_snapshot(&tampered.snapshot(), &verbs),
+        Err(HcGameError::NotYourTurn)
+    );
+}
+
+#[test]
+fn lives_and_scores() {
+    let verbs = lexicon();
+    let config = HcGameConfig {
+        players: vec![String::from("Ann"), String::from("Ben")],
+        lives: 2,
+        changes: 2,
+        answer_time: 0,
+        profile: String::from("lenient"),
+        ..config()
+    };
+    let moves = [
+        change(0, 2_000, "3; sg; aor; act; ind"),
+        // lenient: without the movable ν
+        answer(1, 900_000, "ἐπαίδευσε"),
+        change(1, 900_001, "3; pl; aor; mid; ind"),
+        answer(0, 900_002, "ἐπαιδεύσατο"),
+        change(0, 900_003, "3; sg; aor; act; ind"),
+        answer(1, 900_004, "ἐπαίδευσαν"),
+        change(1, 900_005, "3; pl; aor; mid; ind"),
+        answer(0, 900_006, "ἐπαιδεύσαντο"),
+        change(0, 900_007, "3; sg; aor; act; ind"),
+        answer(1, 900_008, "ἐπαίδευσαν"),
+    ];
+    let game = HcGame::replay(config.clone(), &moves, &verbs).unwrap();
+    let scores: Vec<(u32, u32)> = game.players.iter().map(|p| (p.score, p.lives)).collect();
+    assert_eq!(scores, [(1, 1), (1, 0)]);
+    assert_eq!(game.phase, HcGamePhase::Over { winner: Some(0) });
+
+    // a move which can't be made stops the replay
+    let mut bad = moves.to_vec();
+    bad[2] = change(0, 900_001, "3; pl; aor; mid; ind");
+    assert_eq!(
+        HcGame::replay(config, &bad, &verbs),
+        Err(HcGameError::NotYourTurn)
+    );
+}
+
+#[test]
+fn bad_configs() {
+    let verbs = lexicon();
+    let one_player = HcGameConfig {
+        players: vec![String::from("Ann")],
+        ..config()
+    };
+    assert!(matches!(
+        HcGame::new(one_player, &verbs),
+        Err(HcGameError::Config(_))
+    ));
+    let profile = HcGameConfig {
+        profile: String::from("generous"),
+        ..config()
+    };
+    assert_eq!(
+        HcGame::new(profile, &verbs).unwrap_err().to_string(),
+        "bad game config: unknown grading profile: generous"
+    );
+    let start = HcGameConfig {
+        start: form(1000, "1; sg; pres; act; ind"),
+        ..config()
+    };
+    assert_eq!(
+        HcGame::new(start, &verbs),
+        Err(HcGameError::UnknownVerb(1000))
+    );
+    let start = HcGameConfig {
+        start: form(0, "1; sg; pres,aor; act; ind"),
+        ..config()
+    };
+    assert!(matches!(
+        HcGame::new(start, &verbs),
+        Err(HcGameError::BadForm(_))
+    ));
+    let forever = HcGameConfig {
+        answer_time: u64::MAX,
+        ..config()
+    };
+    assert!(matches!(
+        HcGame::new(forever, &verbs),
+        Err(HcGameError::Config(_))
+    ));
+}
+
+#[test]
+fn late_clock() {
+    let verbs = lexicon();
+    // the deadline can't overflow past the end of time
+    let config = HcGameConfig {
+        answer_time: MAX_ANSWER_TIME,
+        started_at: u64::MAX - 10,
+        ..config()
+    };
+    let mut game = HcGame::new(config, &verbs).unwrap();
+    game.apply(change(0, u64::MAX - 5, "2; sg; pres; act; ind"), &verbs)
+        .unwrap();
+    assert!(matches!(
+        game.phase,
+        HcGamePhase::Answering {
+            deadline: u64::MAX,
+            ..
+        }
+    ));
+    assert!(matches!(
+        game.apply(answer(1, u64::MAX, "παιδεύεις"), &verbs),
+        Ok(HcGameEvent::Answered {
+            correct: true,
+            late: false,
+            ..
+        })
+    ));
+}
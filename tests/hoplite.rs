@@ -0,0 +1,200 @@
+use std::process::{Command, Output};
+
+fn hoplite(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_hoplite"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn form_from_spec() {
+    let output = hoplite(&["form", "παιδεύω", "aor", "mid", "opt", "3", "pl"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "παιδεύσαιντο\n");
+
+    // λῡ́ω in the lexicon
+    let output = hoplite(&["form", "λύω", "aor", "mid", "opt", "3", "pl"]);
+    assert_eq!(stdout(&output), "λῡ́σαιντο\n");
+
+    // lemma without accents, spec in any order
+    let output = hoplite(&["form", "παιδευω", "pl", "3", "ind", "act", "impf"]);
+    assert_eq!(stdout(&output), "ἐπαίδευον\n");
+
+    let output = hoplite(&[
+        "--decompose",
+        "form",
+        "παιδεύω",
+        "aor",
+        "act",
+        "ind",
+        "1",
+        "sg",
+    ]);
+    assert_eq!(stdout(&output), "ε ‐ παιδευσ ‐ α\n");
+
+    let output = hoplite(&[
+        "--dialect",
+        "ionic",
+        "form",
+        "ποιέω",
+        "pres",
+        "act",
+        "ind",
+        "3",
+        "sg",
+    ]);
+    assert_eq!(stdout(&output), "ποιέει\n");
+
+    let output = hoplite(&["form", "φεύγω", "perf", "pass", "ind", "1", "sg"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "φεύγω perf pass ind 1 sg: the verb has no fifth principal part, from which the form \
+         is made\n"
+    );
+
+    let output = hoplite(&["form", "παιδεύω", "aor", "mid"]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = hoplite(&["form", "xyz", "aor", "mid", "ind", "1", "sg"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn paradigm_formats() {
+    let output = hoplite(&["--format", "tsv", "paradigm", "παιδεύω"]);
+    let tsv = stdout(&output);
+    let mut lines = tsv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("lemma\ttense\tvoice\tmood\tperson\tnumber\tform")
+    );
+    assert_eq!(
+        lines.next(),
+        Some("παιδεύω\tPresent\tActive\tIndicative\t1\ts\tπαιδεύω")
+    );
+    assert!(tsv.contains("παιδεύω\tAorist\tPassive\tInfinitive\t\t\tπαιδευθῆναι\n"));
+
+    let output = hoplite(&["--format", "markdown", "paradigm", "παιδεύω"]);
+    assert!(stdout(&output).contains("| 3 | παιδεύει | παιδεύουσι(ν) |\n"));
+}
+
+#[test]
+fn parse_form() {
+    let output = hoplite(&["parse", "ἐπαιδεύσαντο"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "παιδεύω aor mid ind 3 pl\n");
+
+    let output = hoplite(&["parse", "xyz"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn diff_reference() {
+    let output = hoplite(&["diff"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+
+    let reference = std::fs::read_to_string("testdata/new.txt").unwrap();
+    let path = std::env::temp_dir().join("hoplite_diff_reference.txt");
+    std::fs::write(
+        &path,
+        reference.replacen("1s: παιδεύω ;", "1s: παιδευω ;", 1),
+    )
+    .unwrap();
+    let output = hoplite(&["diff", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).ends_with("lines differing: 1\n"));
+}
+
+#[test]
+fn reference_output() {
+    let output = hoplite(&["reference"]);
+    assert_eq!(output.status.code(), Some(0));
+    let reference = std::fs::read_to_string("testdata/new.txt").unwrap();
+    let generated = stdout(&output);
+    assert!(generated
+        .starts_with("\nVerb 0. παιδεύω\n\nPresent Active Indicative\n1s: παιδεύω ; παιδευ ‐ ω\n"));
+
+    // the paradigms, without the labels, match new.txt line for line
+    let forms = |s: &str| -> Vec<String> {
+        s.lines()
+            .filter(|l| l.contains(": ") || l.starts_with("Verb "))
+            .map(|l| l.to_string())
+            .collect()
+    };
+    let generated = forms(&generated);
+    assert_eq!(generated[..], forms(&reference)[..generated.len()]);
+}
+
+#[test]
+fn tag_text() {
+    let path = std::env::temp_dir().join("hoplite_tag_text.txt");
+    std::fs::write(&path, "ἐκέλευσεν αὐτούς.\n").unwrap();
+    let output = hoplite(&["tag", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "# sent_id = 1\n# text = ἐκέλευσεν αὐτούς.\n\
+         1\tἐκέλευσεν\tκελεύω\tVERB\t_\t\
+         Aspect=Perf|Mood=Ind|Number=Sing|Person=3|Tense=Past|VerbForm=Fin|Voice=Act\t_\t_\t_\t_\n\
+         2\tαὐτούς\t_\t_\t_\t_\t_\t_\t_\tSpaceAfter=No\n\
+         3\t.\t.\tPUNCT\t_\t_\t_\t_\t_\t_\n\n"
+    );
+
+    let output = hoplite(&["tag", "no-such-file.txt"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn unimorph_round_trip() {
+    // every form of the whole lexicon would take a while
+    let lexicon = std::env::temp_dir().join("hoplite_unimorph_pp.txt");
+    let pp = std::fs::read_to_string("testdata/pp.txt").unwrap();
+    std::fs::write(&lexicon, pp.lines().take(5).collect::<Vec<_>>().join("\n")).unwrap();
+    let lexicon = lexicon.to_str().unwrap();
+
+    let output = hoplite(&["--lexicon", lexicon, "unimorph"]);
+    assert_eq!(output.status.code(), Some(0));
+    let exported = stdout(&output);
+    assert!(exported.starts_with("παιδεύω\tπαιδεύω\tV;IND;PRS;IPFV;ACT;1;SG\n"));
+
+    let path = std::env::temp_dir().join("hoplite_unimorph.tsv");
+    std::fs::write(&path, &exported).unwrap();
+    let output = hoplite(&[
+        "--lexicon",
+        lexicon,
+        "unimorph-diff",
+        path.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    let rows = exported.lines().count();
+    assert!(stdout(&output).ends_with(&format!(
+        "matching: {}, differing: 0, unknown lemmas: 0, unsupported: 0\n",
+        rows
+    )));
+
+    std::fs::write(
+        &path,
+        "παιδεύω\tἐπαίδευσα\tV;IND;PST;PFV;ACT;3;SG\nπαιδεύω\tx\tN;NOM;SG\n",
+    )
+    .unwrap();
+    let output = hoplite(&[
+        "--lexicon",
+        lexicon,
+        "unimorph-diff",
+        path.to_str().unwrap(),
+    ]);
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(lexicon).unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains(
+        "  παιδεύω V;IND;PST;PFV;ACT;3;SG\n- ἐπαίδευσα\n+ ἐπαίδευσε(ν)\n\
+         matching: 0, differing: 1, unknown lemmas: 0, unsupported: 1\n"
+    ));
+}
@@ -0,0 +1,336 @@
+// random and malformed principal parts through the public entry points, none of which may
+// panic.  The inputs are seeded, so a failure can be reproduced; HOPLITE_RANDOM_VERBS sets
+// how many verbs are tried (default 1500) and HOPLITE_RANDOM_SEED the seed.
+
+use hoplite_verbs_rs::*;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Once};
+
+// xorshift64*, to avoid a dependency
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+static LETTERS: &[&str] = &[
+    "α", "ε", "η", "ι", "ο", "υ", "ω", "ά", "έ", "ή", "ί", "ό", "ύ", "ώ", "ᾶ", "ῆ", "ῖ", "ῦ", "ῶ",
+    "ἀ", "ἐ", "ἠ", "ἰ", "ὀ", "ὑ", "ὠ", "ἁ", "ἑ", "ἡ", "ᾳ", "ῃ", "ῳ", "ᾱ", "ῑ", "ῡ", "ϊ", "ΐ", "β",
+    "γ", "δ", "ζ", "θ", "κ", "λ", "μ", "ν", "ξ", "π", "ρ", "σ", "ς", "τ", "φ", "χ", "ψ", "ῥ",
+    "\u{0301}", "\u{0342}", "\u{0313}", "\u{0304}", "\u{0345}", "—", "/", " ", ",", "-", "x",
+    "(ν)", "ν",
+];
+
+static ENDINGS: &[&str] = &[
+    "ω",
+    "ομαι",
+    "μι",
+    "ημι",
+    "ωμι",
+    "υμι",
+    "αμαι",
+    "ῶ",
+    "οῦμαι",
+    "έω",
+    "άω",
+    "όω",
+    "σω",
+    "σομαι",
+    "ῶμαι",
+    "α",
+    "ον",
+    "ην",
+    "άμην",
+    "όμην",
+    "κα",
+    "α",
+    "μαι",
+    "σμαι",
+    "θην",
+    "ην",
+    "ησα",
+    "ήσω",
+    "ξω",
+    "ψω",
+    "ψα",
+    "ξα",
+    "γμαι",
+    "μμαι",
+    "ειν",
+    "ε",
+    "",
+];
+
+fn random_pp(rng: &mut Rng, lexicon: &[Vec<String>]) -> String {
+    match rng.below(8) {
+        // a real principal part, mutated
+        0..=2 => {
+            let pp = real_pp(rng, lexicon);
+            let mut chars: Vec<char> = pp.chars().collect();
+            match rng.below(5) {
+                0 => chars.truncate(rng.below(chars.len() + 1)),
+                1 => {
+                    let n = rng.below(chars.len() + 1);
+                    chars.drain(..n);
+                }
+                2 if !chars.is_empty() => {
+                    let i = rng.below(chars.len());
+                    chars[i] = rng.pick(LETTERS).chars().next().unwrap_or('α');
+                }
+                3 => chars.extend(rng.pick(ENDINGS).chars()),
+                _ => (),
+            }
+            chars.into_iter().collect()
+        }
+        // a real principal part with another ending
+        3 => {
+            let pp = real_pp(rng, lexicon);
+            let keep = pp.chars().count().saturating_sub(rng.below(5));
+            let stem: String = pp.chars().take(keep).collect();
+            format!("{}{}", stem, rng.pick(ENDINGS))
+        }
+        // random letters with a verb ending
+        4 | 5 => {
+            let mut s = String::new();
+            for _ in 0..rng.below(6) {
+                s.push_str(rng.pick::<&str>(LETTERS));
+            }
+            s.push_str(rng.pick::<&str>(ENDINGS));
+            s
+        }
+        6 if rng.below(50) == 0 => format!("ἐ{}ω", "στρ".repeat(100)),
+        6 => String::from(*rng.pick(&["—", "", " ", "/", " / ", "—/—", "ω", "μι", "μαι", "ἐ"])),
+        // alternates
+        _ => format!("{} / {}", real_pp(rng, lexicon), rng.pick(ENDINGS)),
+    }
+}
+
+fn real_pp(rng: &mut Rng, lexicon: &[Vec<String>]) -> String {
+    let pps = rng.pick(lexicon);
+    rng.pick(pps).clone()
+}
+
+fn random_verb(rng: &mut Rng, lexicon: &[Vec<String>], id: u32) -> HcGreekVerb {
+    // mostly six principal parts, as from_string makes them, but the field is public
+    let count = match rng.below(20) {
+        0 => rng.below(6),
+        1 => 7,
+        _ => 6,
+    };
+    let pps = if rng.below(3) == 0 {
+        // a real verb with one or two parts replaced
+        let mut pps = rng.pick(lexicon).clone();
+        for _ in 0..1 + rng.below(2) {
+            let i = rng.below(pps.len());
+            pps[i] = random_pp(rng, lexicon);
+        }
+        pps.resize(count, String::from("—"));
+        pps
+    } else {
+        (0..count).map(|_| random_pp(rng, lexicon)).collect()
+    };
+    let properties = match rng.below(4) {
+        0 => rng.next() as u32,
+        1 => 0,
+        _ => 1 << rng.below(32),
+    };
+    HcGreekVerb {
+        id,
+        pps,
+        properties,
+        hq_unit: rng.below(25) as u32,
+    }
+}
+
+fn random_form(rng: &mut Rng, verb: &Arc<HcGreekVerb>) -> HcGreekVerbForm {
+    let mood = *rng.pick(&[
+        HcMood::Indicative,
+        HcMood::Subjunctive,
+        HcMood::Optative,
+        HcMood::Imperative,
+        HcMood::Infinitive,
+        HcMood::Participle,
+    ]);
+    let maybe = |rng: &mut Rng, wanted: bool| wanted == (rng.below(10) != 0);
+    let finite = !matches!(mood, HcMood::Infinitive | HcMood::Participle);
+    HcGreekVerbForm {
+        verb: verb.clone(),
+        person: maybe(rng, finite)
+            .then(|| *rng.pick(&[HcPerson::First, HcPerson::Second, HcPerson::Third])),
+        number: maybe(rng, mood != HcMood::Infinitive)
+            .then(|| *rng.pick(&[HcNumber::Singular, HcNumber::Dual, HcNumber::Plural])),
+        tense: *rng.pick(&[
+            HcTense::Present,
+            HcTense::Imperfect,
+            HcTense::Future,
+            HcTense::Aorist,
+            HcTense::Perfect,
+            HcTense::Pluperfect,
+        ]),
+        voice: *rng.pick(&[HcVoice::Active, HcVoice::Middle, HcVoice::Passive]),
+        mood,
+        gender: maybe(rng, mood == HcMood::Participle)
+            .then(|| *rng.pick(&[HcGender::Masculine, HcGender::Feminine, HcGender::Neuter])),
+        case: maybe(rng, mood == HcMood::Participle).then(|| {
+            *rng.pick(&[
+                HcCase::Nominative,
+                HcCase::Genitive,
+                HcCase::Dative,
+                HcCase::Accusative,
+                HcCase::Vocative,
+            ])
+        }),
+    }
+}
+
+fn exercise(vf: &HcGreekVerbForm, prev: &HcGreekVerbForm, options: &HcFormOptions) {
+    let verb = &vf.verb;
+    let _ = verb.get_verb_lemma();
+    let _ = verb.deponent_type();
+    let _ = check_pps(&verb.pps.join(", "), verb);
+    let _ = input::normalize_input(&verb.pps.join(", "));
+    let _ = vf.is_legal_form();
+    let _ = vf.get_pp_num();
+    let _ = vf.get_pp();
+    let _ = vf.get_label();
+    let _ = vf.get_description(prev, "<b>", "</b>");
+    let _ = vf.get_description_abbrev(prev, "<b>", "</b>");
+    for decompose in [false, true] {
+        let form = vf
+            .get_form(decompose)
+            .map(|steps| steps.last().unwrap().form.to_string());
+        let mut into = String::new();
+        let result = vf.get_form_into(decompose, &mut into);
+        assert_eq!(result.map(|_| into), form);
+        let alternates = vf.get_form_alternates(decompose);
+        assert_eq!(alternates.map(|a| join_alternates(&a)), form);
+        let _ = vf.get_form_with_options(decompose, options);
+    }
+}
+
+thread_local! {
+    // the panic caught by quietly, if any
+    static CAUGHT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// runs f, returning where and why it panicked instead of printing it
+fn quietly<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let caught = CAUGHT.with(|c| {
+                let mut c = c.borrow_mut();
+                if c.is_some() {
+                    *c = Some(info.to_string());
+                }
+                c.is_some()
+            });
+            if !caught {
+                default(info);
+            }
+        }));
+    });
+    CAUGHT.with(|c| *c.borrow_mut() = Some(String::new()));
+    let result = catch_unwind(AssertUnwindSafe(f));
+    let caught = CAUGHT.with(|c| c.borrow_mut().take()).unwrap_or_default();
+    result.map_err(|_| caught)
+}
+
+// the first input for each place that panicked
+fn report(failures: &BTreeMap<String, (usize, String)>) -> String {
+    failures
+        .iter()
+        .map(|(panic, (count, input))| format!("{} ({} times), e.g.\n  {}\n", panic, count, input))
+        .collect()
+}
+
+fn record(failures: &mut BTreeMap<String, (usize, String)>, panic: String, input: String) {
+    failures.entry(panic).or_insert((0, input)).0 += 1;
+}
+
+#[test]
+fn random_principal_parts_never_panic() {
+    let lexicon: Vec<Vec<String>> = std::fs::read_to_string("testdata/pp.txt")
+        .unwrap()
+        .lines()
+        .filter_map(|line| HcGreekVerb::from_string_with_properties(0, line))
+        .map(|v| v.pps)
+        .collect();
+    let verbs = std::env::var("HOPLITE_RANDOM_VERBS")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(1500);
+    let seed = std::env::var("HOPLITE_RANDOM_SEED")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0x5EED_C0DE);
+    let mut rng = Rng(seed);
+
+    let mut failures = BTreeMap::new();
+    for id in 0..verbs {
+        let verb = Arc::new(random_verb(&mut rng, &lexicon, id));
+        let mut prev = random_form(&mut rng, &verb);
+        for _ in 0..12 {
+            let vf = random_form(&mut rng, &verb);
+            let options = HcFormOptions {
+                extended_perfect: rng.below(2) == 0,
+                dialect: *rng.pick(&[
+                    HcDialect::Attic,
+                    HcDialect::Ionic,
+                    HcDialect::Homeric,
+                    HcDialect::Koine,
+                ]),
+            };
+            if let Err(panic) = quietly(|| exercise(&vf, &prev, &options)) {
+                record(&mut failures, panic, format!("{:?} {:?}", vf, options));
+            }
+            prev = vf;
+        }
+    }
+    assert!(failures.is_empty(), "{}", report(&failures));
+}
+
+#[test]
+fn random_lexicon_lines_never_panic() {
+    let mut rng = Rng(42);
+    let mut failures = BTreeMap::new();
+    for _ in 0..2000 {
+        let mut line = String::new();
+        for _ in 0..rng.below(12) {
+            line.push_str(rng.pick::<&str>(&[
+                "παιδεύω",
+                ",",
+                " ",
+                "%",
+                "3",
+                "x",
+                "-1",
+                "99999999999",
+                "PREFIXED",
+                "—",
+                "",
+                "CONSONANT_STEM_PERFECT_PI",
+            ]));
+        }
+        if let Err(panic) = quietly(|| HcGreekVerb::from_string_with_properties(0, &line)) {
+            record(&mut failures, panic, format!("{:?}", line));
+        }
+    }
+    assert!(failures.is_empty(), "{}", report(&failures));
+}
@@ -0,0 +1,168 @@
+#![cfg(feature = "server")]
+
+use hoplite_verbs_rs::server::*;
+use std::fs::File;
+use std::io::BufReader;
+
+// the server's handler called directly, without a socket
+fn client() -> HcServer {
+    HcServer::from_lexicon(BufReader::new(File::open("testdata/pp.txt").unwrap())).unwrap()
+}
+
+fn post<T: for<'a> serde::Deserialize<'a>>(server: &HcServer, path: &str, body: &str) -> (u16, T) {
+    let response = server.handle("POST", path, body);
+    (
+        response.status,
+        serde_json::from_str(&response.body).unwrap(),
+    )
+}
+
+#[test]
+fn generate() {
+    let server = client();
+    let (status, res): (u16, GenerateResponse) = post(
+        &server,
+        "/generate",
+        r#"{"verb": "παιδεύω", "form": {"person": "3", "number": "p", "tense": "Aorist", "voice": "Middle", "mood": "Optative"}}"#,
+    );
+    assert_eq!(status, 200);
+    assert_eq!(res.form, "παιδεύσαιντο");
+    assert_eq!(res.steps.last().unwrap().explanation, "Accent verb");
+
+    let (_, res): (u16, GenerateResponse) = post(
+        &server,
+        "/generate",
+        r#"{"verb": 0, "form": {"person": "1", "number": "s", "tense": "Aorist", "voice": "Active", "mood": "Indicative"}, "decompose": true}"#,
+    );
+    assert_eq!(res.form, "ε ‐ παιδευσ ‐ α");
+
+    let (_, res): (u16, GenerateResponse) = post(
+        &server,
+        "/generate",
+        r#"{"verb": "ποιέω", "form": {"person": "3", "number": "s", "tense": "Present", "voice": "Active", "mood": "Indicative"}, "options": {"dialect": "Ionic"}}"#,
+    );
+    assert_eq!(res.form, "ποιέει");
+
+    let (status, res): (u16, ErrorResponse) = post(
+        &server,
+        "/generate",
+        r#"{"verb": "φεύγω", "form": {"person": "1", "number": "s", "tense": "Perfect", "voice": "Passive", "mood": "Indicative"}}"#,
+    );
+    assert_eq!((status, res.error.as_str()), (422, "BlankPrincipalPart"));
+    assert_eq!(
+        res.reason.as_deref(),
+        Some("the verb has no fifth principal part, from which the form is made")
+    );
+
+    let (status, _): (u16, ErrorResponse) = post(
+        &server,
+        "/generate",
+        r#"{"verb": "παιδεύω", "form": {"tense": "Aorist", "voice": "Middle", "mood": "Gerund"}}"#,
+    );
+    assert_eq!(status, 400);
+    let (status, _): (u16, ErrorResponse) = post(&server, "/generate", "{");
+    assert_eq!(status, 400);
+    let (status, _): (u16, ErrorResponse) = post(
+        &server,
+        "/generate",
+        r#"{"verb": 1000, "form": {"tense": "Aorist", "voice": "Middle", "mood": "Infinitive"}}"#,
+    );
+    assert_eq!(status, 404);
+    assert_eq!(server.handle("GET", "/generate", "").status, 405);
+    assert_eq!(server.handle("POST", "/nothing", "{}").status, 404);
+}
+
+#[test]
+fn paradigm() {
+    let server = client();
+    let (status, res): (u16, ParadigmResponse) = post(&server, "/paradigm", r#"{"verb": 0}"#);
+    assert_eq!(status, 200);
+    assert_eq!(res.principal_parts[0], "παιδεύω");
+    assert_eq!(res.forms[0].result.as_deref(), Some("παιδεύω"));
+    let aorist_passive_infinitive = res
+        .forms
+        .iter()
+        .find(|f| {
+            f.form.tense == "Aorist" && f.form.voice == "Passive" && f.form.mood == "Infinitive"
+        })
+        .unwrap();
+    assert_eq!(
+        aorist_passive_infinitive.result.as_deref(),
+        Some("παιδευθῆναι")
+    );
+}
+
+#[test]
+fn query_string() {
+    let server = client();
+    let (status, res): (u16, ParadigmResponse) =
+        post(&server, "/paradigm?cache=0", r#"{"verb": 0}"#);
+    assert_eq!(status, 200);
+    assert_eq!(res.principal_parts[0], "παιδεύω");
+    assert_eq!(
+        server.handle("POST", "/paradigms?cache=0", "{}").status,
+        404
+    );
+}
+
+#[test]
+fn quiz() {
+    let server = client();
+    let request = r#"{"params": "9,14,17", "max_unit": 4, "seed": 12345}"#;
+    let (status, question): (u16, QuizResponse) = post(&server, "/quiz/next", request);
+    assert_eq!(status, 200);
+    assert_eq!(question.form.tense, "Aorist");
+    assert_eq!(question.form.mood, "Optative");
+    assert_eq!(question.form.voice, "Middle");
+    assert!(question.verb_id < 20);
+
+    // the same seed gives the same question
+    let (_, again): (u16, QuizResponse) = post(&server, "/quiz/next", request);
+    assert_eq!(question, again);
+
+    let (status, _): (u16, ErrorResponse) = post(&server, "/quiz/next", r#"{"max_unit": 0}"#);
+    assert_eq!(status, 422);
+
+    let grade = |answer: &str| {
+        let body = serde_json::json!({
+            "verb": question.verb_id,
+            "form": question.form,
+            "answer": answer,
+        });
+        let (status, res): (u16, GradeResponse) = post(&server, "/quiz/grade", &body.to_string());
+        assert_eq!(status, 200);
+        res
+    };
+    let correct_answer = grade("").correct_answer;
+    assert!(!grade("").correct);
+    assert!(grade(&correct_answer).correct);
+    assert_eq!(grade("").profile, "standard");
+    assert!(grade("").normalizations.is_empty());
+
+    // λαβέ with a final grave
+    let grade = |profile: &str| {
+        let body = serde_json::json!({
+            "verb": "λαμβάνω",
+            "form": {"person": "2", "number": "s", "tense": "Aorist", "voice": "Active", "mood": "Imperative"},
+            "answer": "λαβὲ",
+            "profile": profile,
+        });
+        post::<GradeResponse>(&server, "/quiz/grade", &body.to_string())
+    };
+    let (status, res) = grade("lenient");
+    assert_eq!(status, 200);
+    assert!(res.correct);
+    assert_eq!(res.profile, "lenient");
+    assert_eq!(res.normalizations, Vec::<String>::new());
+    let body = r#"{"verb": "λαμβάνω", "form": {"person": "2", "number": "s", "tense": "Aorist", "voice": "Active", "mood": "Imperative"}, "answer": "λαβε\u0301"}"#;
+    let (_, res): (u16, GradeResponse) = post(&server, "/quiz/grade", body);
+    assert!(res.correct);
+    assert_eq!(res.normalizations, ["CombiningMarks"]);
+    assert!(!grade("standard").1.correct);
+    let (status, _): (u16, ErrorResponse) = post(
+        &server,
+        "/quiz/grade",
+        r#"{"verb": 0, "form": {"person": "1", "number": "s", "tense": "Aorist", "voice": "Active", "mood": "Indicative"}, "answer": "", "profile": "generous"}"#,
+    );
+    assert_eq!(status, 400);
+}
@@ -0,0 +1,191 @@
+#![cfg(feature = "srs")]
+
+use hoplite_verbs_rs::grading::*;
+use hoplite_verbs_rs::parameters::HcParameterSet;
+use hoplite_verbs_rs::srs::*;
+use hoplite_verbs_rs::*;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+
+const DAY: u64 = 24 * 60 * 60;
+const START: u64 = 1_700_000_000;
+
+fn lexicon() -> Vec<Arc<HcGreekVerb>> {
+    read_lexicon(BufReader::new(File::open("testdata/pp.txt").unwrap())).unwrap()
+}
+
+fn principal_part(verb_id: u32, part: HcGreekPrincipalParts) -> HcReviewItem {
+    HcReviewItem::PrincipalPart { verb_id, part }
+}
+
+#[test]
+fn schedule() {
+    let verbs = lexicon();
+    let profile = HcGradingProfile::default();
+    let mut scheduler = HcScheduler::new(2);
+    scheduler.parameters = "aor; act; ind".parse().unwrap();
+
+    // a verb's principal parts are introduced first
+    assert_eq!(
+        scheduler.next(&verbs, START),
+        Some(principal_part(0, HcGreekPrincipalParts::First))
+    );
+    let grade = grade_pps(
+        "παιδεύω, παιδεύσω, ἐπαίδευσα, πεπαίδευκα, πεπαίδευμαι, ἐπαιδεύθη",
+        &verbs[0],
+        &profile,
+    );
+    scheduler.review_pps(&verbs[0], &grade, START);
+    assert_eq!(scheduler.len(), 6);
+    let first = scheduler
+        .state(&principal_part(0, HcGreekPrincipalParts::First))
+        .unwrap();
+    assert_eq!((first.interval, first.due), (1, START + DAY));
+    let sixth = scheduler
+        .state(&principal_part(0, HcGreekPrincipalParts::Sixth))
+        .unwrap();
+    assert_eq!((sixth.repetitions, sixth.interval), (0, 1));
+    assert!(sixth.ease < first.ease);
+
+    // then its forms, in the parameters given
+    let item = scheduler.next(&verbs, START).unwrap();
+    let vf = item.to_form(&verbs[0]).unwrap();
+    assert_eq!(
+        HcParameterSet::from(&vf).to_string(),
+        "1; sg; aor; act; ind"
+    );
+    let grade = grade_form(&vf, "ἐπαίδευσα", &profile).unwrap();
+    scheduler.review_form(&vf, &grade, START);
+    assert_eq!(scheduler.state(&item).unwrap().interval, 1);
+
+    // intervals of 1 day, 6 days, then times the ease; a wrong answer starts over
+    scheduler.review_form(&vf, &grade, START + DAY);
+    assert_eq!(scheduler.state(&item).unwrap().interval, 6);
+    scheduler.review_form(&vf, &grade, START + 7 * DAY);
+    let state = scheduler.state(&item).unwrap();
+    assert_eq!((state.interval, state.due), (16, START + 23 * DAY));
+    let wrong = grade_form(&vf, "ἐπαίδευσε", &profile).unwrap();
+    scheduler.review_form(&vf, &wrong, START + 23 * DAY);
+    let state = scheduler.state(&item).unwrap();
+    assert_eq!((state.repetitions, state.interval, state.lapses), (0, 1, 1));
+
+    // a normalized answer counts for a little less: the ease stays as it was
+    let typed = grade_form(&vf, "ἐπαι\u{301}δευσα", &profile).unwrap();
+    assert!(typed.correct);
+    let ease = scheduler.state(&item).unwrap().ease;
+    scheduler.review_form(&vf, &typed, START + 24 * DAY);
+    assert!((scheduler.state(&item).unwrap().ease - ease).abs() < 1e-9);
+    scheduler.review_form(&vf, &grade, START + 25 * DAY);
+    assert!(scheduler.state(&item).unwrap().ease > ease);
+
+    // what is due, the most overdue first
+    let due = scheduler.due(&verbs, START + 2 * DAY);
+    assert_eq!(due.len(), 6);
+    assert_eq!(due[0], principal_part(0, HcGreekPrincipalParts::First));
+    assert_eq!(scheduler.next(&verbs, START + 2 * DAY), Some(due[0]));
+
+    // verbs of later units are not scheduled
+    let grade = grade_pps(&verbs[4].pps.join(", "), &verbs[4], &profile);
+    scheduler.review_pps(&verbs[4], &grade, START);
+    assert!(scheduler
+        .due(&verbs, START + 30 * DAY)
+        .iter()
+        .all(|item| item.verb_id() == 0));
+    scheduler.max_unit = 3;
+    assert!(scheduler
+        .due(&verbs, START + 30 * DAY)
+        .iter()
+        .any(|item| item.verb_id() == 4));
+}
+
+#[test]
+fn normalized_principal_parts() {
+    let verbs = lexicon();
+    let mut scheduler = HcScheduler::new(2);
+    // a blank typed as --- is no slip; only the part with the combining accent was
+    let grade = grade_pps(
+        "ἐθέλω, ἐθελήσω, ἠθε\u{301}λησα, ἠθέληκα, ---, ---",
+        &verbs[9],
+        &HcGradingProfile::default(),
+    );
+    assert_eq!(grade.correct, [true; 6]);
+    scheduler.review_pps(&verbs[9], &grade, START);
+    let ease = |part| scheduler.state(&principal_part(9, part)).unwrap().ease;
+    let first = ease(HcGreekPrincipalParts::First);
+    assert!(ease(HcGreekPrincipalParts::Third) < first);
+    for part in [
+        HcGreekPrincipalParts::Second,
+        HcGreekPrincipalParts::Fourth,
+        HcGreekPrincipalParts::Fifth,
+        HcGreekPrincipalParts::Sixth,
+    ] {
+        assert!((ease(part) - first).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn new_items_per_day() {
+    let verbs = lexicon();
+    let mut scheduler = HcScheduler::new(2);
+    scheduler.new_per_day = 1;
+    let item = scheduler.next(&verbs, START).unwrap();
+    scheduler.review(item, 5, START);
+    assert_eq!(scheduler.next(&verbs, START + 60), None);
+    assert_eq!(
+        scheduler.next(&verbs, START + DAY),
+        Some(principal_part(0, HcGreekPrincipalParts::First))
+    );
+
+    // a blank principal part isn't drilled
+    let mut scheduler = HcScheduler::new(4);
+    scheduler.new_per_day = 100;
+    let ethelw = verbs.iter().find(|v| v.pps[0] == "ἐθέλω").unwrap();
+    let mut seen = vec![];
+    while let Some(HcReviewItem::PrincipalPart { verb_id, part }) =
+        scheduler.next(std::slice::from_ref(ethelw), START)
+    {
+        seen.push(part);
+        scheduler.review(principal_part(verb_id, part), 5, START);
+    }
+    assert_eq!(
+        seen,
+        [
+            HcGreekPrincipalParts::First,
+            HcGreekPrincipalParts::Second,
+            HcGreekPrincipalParts::Third,
+            HcGreekPrincipalParts::Fourth
+        ]
+    );
+}
+
+#[test]
+fn save_and_load() {
+    let verbs = lexicon();
+    let mut scheduler = HcScheduler::new(5);
+    scheduler.parameters = "fut; mid".parse().unwrap();
+    for i in 0..10 {
+        let item = scheduler.next(&verbs, START).unwrap();
+        scheduler.review(item, (i % 6) as u8, START + i);
+    }
+
+    let mut file = Vec::new();
+    scheduler.write(&mut file).unwrap();
+    let json = String::from_utf8(file.clone()).unwrap();
+    assert!(json.contains(r#""parameters": "fut; mid""#));
+    assert!(json.contains(r#""principal_part": 1"#));
+    assert!(json.contains(r#""form": "1; sg; fut; mid; ind""#));
+    let loaded = HcScheduler::read(&mut file.as_slice()).unwrap();
+    assert_eq!(loaded, scheduler);
+
+    // the same state gives the same file
+    let mut again = Vec::new();
+    loaded.write(&mut again).unwrap();
+    assert_eq!(again, file);
+
+    let newer = json.replace(r#""version": 1"#, r#""version": 2"#);
+    assert!(HcScheduler::read(&mut newer.as_bytes()).is_err());
+    let bad = json.replace(r#""principal_part": 1"#, r#""principal_part": 7"#);
+    assert!(HcScheduler::read(&mut bad.as_bytes()).is_err());
+    assert!(HcScheduler::read(&mut "{".as_bytes()).is_err());
+}